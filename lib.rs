@@ -2,21 +2,60 @@
 
 use ink_lang as ink;
 
+pub use self::uke_human_dns::{UkeHumanDns, UkeHumanDnsRef};
+
+/// Name resolution interface of the Human DNS for other ink! contracts.
+///
+/// A dependent contract adds this crate with the `ink-as-dependency` feature, builds a
+/// reference to the deployed registry from its address, and calls the messages through it:
+///
+/// ```ignore
+/// use ink_env::call::FromAccountId;
+/// use uke_human_dns::{Resolver, UkeHumanDnsRef};
+///
+/// let registry: UkeHumanDnsRef = FromAccountId::from_account_id(registry_address);
+/// let recipient = registry.resolve(name);
+/// ```
+#[ink::trait_definition]
+pub trait Resolver {
+    /// Resolves a username to its account, or `None` if it is unregistered or expired.
+    #[ink(message)]
+    fn resolve(&self, name: ink_env::Hash) -> Option<ink_env::AccountId>;
+
+    /// Retrieves the username hash registered to an account, if any.
+    #[ink(message)]
+    fn get_name(&self, who: ink_env::AccountId) -> Option<ink_env::Hash>;
+}
+
 #[ink::contract]
 
 mod uke_human_dns {
 
-    use ink_storage::{traits::SpreadAllocate, Mapping};
+    use super::Resolver;
+    use ink_env::hash::Blake2x256;
+    use ink_prelude::vec::Vec;
+    use ink_storage::{
+        traits::{KeyPtr, PackedLayout, SpreadAllocate, SpreadLayout},
+        Mapping,
+    };
     /// Emitted whenever a new user is registered.
+    ///
+    /// The `id` is sequential and stays with the registration across renames.
     #[ink(event)]
     pub struct Register {
         #[ink(topic)]
         name: Hash,
         #[ink(topic)]
         from: AccountId,
+        #[ink(topic)]
+        id: u32,
+        timestamp: Timestamp,
     }
 
     /// Emitted whenever a username gets updated
+    ///
+    /// A rename swapping one name hash for another emits `EditUsername`, whereas pointing a
+    /// name at a different address emits `ResolutionChanged`.
     #[ink(event)]
     pub struct EditUsername {
         #[ink(topic)]
@@ -27,6 +66,320 @@ mod uke_human_dns {
         from: AccountId,
     }
 
+    /// Emitted whenever the address a username resolves to changes without a rename.
+    #[ink(event)]
+    pub struct ResolutionChanged {
+        #[ink(topic)]
+        name: Hash,
+        #[ink(topic)]
+        old_target: AccountId,
+        #[ink(topic)]
+        new_target: AccountId,
+    }
+
+    /// Emitted whenever a username gets unregistered.
+    #[ink(event)]
+    pub struct Unregister {
+        #[ink(topic)]
+        name: Hash,
+        #[ink(topic)]
+        from: AccountId,
+    }
+
+    /// Emitted whenever a username gets transferred to another account.
+    #[ink(event)]
+    pub struct NameTransferred {
+        #[ink(topic)]
+        name: Hash,
+        #[ink(topic)]
+        from: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+    }
+
+    /// Emitted whenever the owner withdraws funds from the contract.
+    #[ink(event)]
+    pub struct Withdrawn {
+        #[ink(topic)]
+        to: AccountId,
+        amount: Balance,
+    }
+
+    /// Emitted whenever a username gets renewed.
+    #[ink(event)]
+    pub struct Renewed {
+        #[ink(topic)]
+        name: Hash,
+        renewer: AccountId,
+        new_expiry: Timestamp,
+    }
+
+    /// Emitted whenever an expired username gets claimed by a new owner.
+    #[ink(event)]
+    pub struct NameReclaimed {
+        #[ink(topic)]
+        name: Hash,
+        #[ink(topic)]
+        previous_owner: AccountId,
+        #[ink(topic)]
+        new_owner: AccountId,
+    }
+
+    /// Emitted whenever an account publishes a new public encryption key.
+    ///
+    /// Carries the replaced key so peers can verify the rotation; `old_key` is `None` the
+    /// first time an account publishes a key.
+    #[ink(event)]
+    pub struct PubkeyRotated {
+        #[ink(topic)]
+        who: AccountId,
+        old_key: Option<[u8; 32]>,
+        new_key: [u8; 32],
+    }
+
+    /// Emitted whenever the contract gets paused.
+    #[ink(event)]
+    pub struct Paused {
+        #[ink(topic)]
+        by: AccountId,
+    }
+
+    /// Emitted whenever the contract gets unpaused.
+    #[ink(event)]
+    pub struct Unpaused {
+        #[ink(topic)]
+        by: AccountId,
+    }
+
+    /// Emitted whenever the owner nominates a new contract owner.
+    #[ink(event)]
+    pub struct OwnershipTransferStarted {
+        #[ink(topic)]
+        previous_owner: AccountId,
+        #[ink(topic)]
+        new_owner: AccountId,
+    }
+
+    /// Emitted whenever a nominated owner accepts ownership of the contract.
+    #[ink(event)]
+    pub struct OwnershipTransferred {
+        #[ink(topic)]
+        previous_owner: AccountId,
+        #[ink(topic)]
+        new_owner: AccountId,
+    }
+
+    /// Emitted whenever a username gets offered to another account.
+    #[ink(event)]
+    pub struct NameOffered {
+        #[ink(topic)]
+        name: Hash,
+        #[ink(topic)]
+        from: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+    }
+
+    /// Emitted whenever an offered username gets claimed by its recipient.
+    #[ink(event)]
+    pub struct NameClaimed {
+        #[ink(topic)]
+        name: Hash,
+        #[ink(topic)]
+        from: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+    }
+
+    /// Emitted whenever a listed username gets bought.
+    #[ink(event)]
+    pub struct NameSold {
+        #[ink(topic)]
+        name: Hash,
+        #[ink(topic)]
+        from: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+        price: Balance,
+    }
+
+    /// Emitted whenever the owner of a username puts it up for auction.
+    #[ink(event)]
+    pub struct AuctionStarted {
+        #[ink(topic)]
+        name: Hash,
+        #[ink(topic)]
+        seller: AccountId,
+        end: Timestamp,
+    }
+
+    /// Emitted whenever a bid becomes the highest bid of an auction.
+    #[ink(event)]
+    pub struct BidPlaced {
+        #[ink(topic)]
+        name: Hash,
+        #[ink(topic)]
+        bidder: AccountId,
+        amount: Balance,
+    }
+
+    /// Emitted whenever an auction gets settled; `winner` is `None` if the name did not change hands.
+    #[ink(event)]
+    pub struct AuctionSettled {
+        #[ink(topic)]
+        name: Hash,
+        #[ink(topic)]
+        winner: Option<AccountId>,
+        amount: Balance,
+    }
+
+    /// Emitted whenever the owner forcibly releases a username.
+    #[ink(event)]
+    pub struct AdminReleased {
+        #[ink(topic)]
+        name: Hash,
+        #[ink(topic)]
+        former_owner: AccountId,
+    }
+
+    /// Emitted whenever the owner freezes a username.
+    #[ink(event)]
+    pub struct Frozen {
+        #[ink(topic)]
+        name: Hash,
+    }
+
+    /// Emitted whenever the owner unfreezes a username.
+    #[ink(event)]
+    pub struct Unfrozen {
+        #[ink(topic)]
+        name: Hash,
+    }
+
+    /// Emitted whenever a guardian recovers a username to a new account.
+    #[ink(event)]
+    pub struct Recovered {
+        #[ink(topic)]
+        name: Hash,
+        #[ink(topic)]
+        guardian: AccountId,
+        #[ink(topic)]
+        new_owner: AccountId,
+    }
+
+    /// Emitted whenever the owner replaces the code of the contract.
+    #[ink(event)]
+    pub struct CodeUpgraded {
+        #[ink(topic)]
+        code_hash: Hash,
+    }
+
+    /// Emitted whenever the owner of a username assigns a subname under it.
+    #[ink(event)]
+    pub struct SubnameCreated {
+        #[ink(topic)]
+        parent: Hash,
+        #[ink(topic)]
+        label: Hash,
+        #[ink(topic)]
+        to: AccountId,
+    }
+
+    /// Emitted whenever the owner changes the address unresolved names fall back to.
+    #[ink(event)]
+    pub struct DefaultAddressChanged {
+        #[ink(topic)]
+        old_address: AccountId,
+        #[ink(topic)]
+        new_address: AccountId,
+    }
+
+    /// Emitted whenever the owner of a username changes its content hash.
+    #[ink(event)]
+    pub struct ContentHashChanged {
+        #[ink(topic)]
+        name: Hash,
+        cid: Vec<u8>,
+    }
+
+    /// Emitted whenever the owner of a username leases it to another account.
+    #[ink(event)]
+    pub struct Leased {
+        #[ink(topic)]
+        name: Hash,
+        lessor: AccountId,
+        #[ink(topic)]
+        lessee: AccountId,
+        until: Timestamp,
+    }
+
+    /// Emitted whenever the owner of a username ends its lease.
+    #[ink(event)]
+    pub struct LeaseEnded {
+        #[ink(topic)]
+        name: Hash,
+        lessee: AccountId,
+    }
+
+    /// Emitted alongside the specific events of the main lifecycle changes of a username,
+    /// if enabled through `set_state_changed_events`.
+    ///
+    /// `kind` is 0 for a registration, 1 for a rename (emitted for the new name), 2 for a
+    /// transfer and 3 for a release.
+    #[ink(event)]
+    pub struct StateChanged {
+        #[ink(topic)]
+        name: Hash,
+        kind: u8,
+    }
+
+    /// Emitted whenever the owner of a username aliases it to another name or removes the
+    /// alias; `target_name` is `None` on removal.
+    #[ink(event)]
+    pub struct AliasChanged {
+        #[ink(topic)]
+        name: Hash,
+        target_name: Option<Hash>,
+    }
+
+    /// Emitted whenever a username gets resolved through `resolve_logged`.
+    #[ink(event)]
+    pub struct Resolved {
+        #[ink(topic)]
+        name: Hash,
+        #[ink(topic)]
+        by: AccountId,
+    }
+
+    /// Emitted whenever the owner reserves usernames through `reserve_many`; `count` is the
+    /// number of names that were not reserved before.
+    #[ink(event)]
+    pub struct BulkReserved {
+        count: u32,
+    }
+
+    /// Emitted whenever a text record gets deleted from a username.
+    #[ink(event)]
+    pub struct TextRecordDeleted {
+        #[ink(topic)]
+        name: Hash,
+        key: Vec<u8>,
+    }
+
+    /// Emitted whenever the owner approves a registrar.
+    #[ink(event)]
+    pub struct RegistrarAdded {
+        #[ink(topic)]
+        who: AccountId,
+    }
+
+    /// Emitted whenever the owner revokes a registrar.
+    #[ink(event)]
+    pub struct RegistrarRemoved {
+        #[ink(topic)]
+        who: AccountId,
+    }
+
     /// Uke Human DNS ink! Smart Contract.  
     /// 
     /// Used to keep a registry of hash representations of human-readable usernames to AccountIds using ink!.
@@ -41,10 +394,144 @@ mod uke_human_dns {
     #[ink(storage)]
     #[derive(SpreadAllocate)]
     pub struct UkeHumanDns {
-        /// User mapping of username hashes to accounts.
+        /// User mapping of username hashes to the accounts they resolve to.
         username_to_id: Mapping<Hash, AccountId>,
+        /// Owner, registration time and flags of each username, packed into a single entry.
+        records: Mapping<Hash, Record>,
+        /// Primary username hash declared by accounts owning several names.
+        primary_name: Mapping<AccountId, Hash>,
         /// Default address of the contract.
         default_address: AccountId,
+        /// Administrator of the contract.
+        owner: AccountId,
+        /// Account nominated to become the next administrator.
+        pending_owner: Option<AccountId>,
+        /// Number of currently registered usernames.
+        registration_count: u32,
+        /// Fee in native tokens required to register a username.
+        registration_fee: Balance,
+        /// Lifetime of a registration before the username expires.
+        ttl: Timestamp,
+        /// Period after expiry during which a username still resolves but cannot be reclaimed.
+        grace_period: Timestamp,
+        /// Creation time of the pending registration commitments of each committer.
+        commitments: Mapping<(AccountId, Hash), Timestamp>,
+        /// Minimum age of a commitment before it can be revealed.
+        min_commitment_age: Timestamp,
+        /// Maximum number of entries accepted by batch messages.
+        max_batch: u32,
+        /// Public encryption keys published by accounts for end-to-end messaging.
+        pubkeys: Mapping<AccountId, [u8; 32]>,
+        /// Address records of usernames for other chains, keyed by coin type.
+        addresses: Mapping<(Hash, u32), Vec<u8>>,
+        /// Coin types of the address records set on each username, in a dense list per username.
+        coin_types: Mapping<(Hash, u32), u32>,
+        /// Number of address records set on each username.
+        coin_type_count: Mapping<Hash, u32>,
+        /// Text records of usernames keyed by record key.
+        text_records: Mapping<(Hash, Vec<u8>), Vec<u8>>,
+        /// Maximum length of text record keys and values.
+        max_text_len: u32,
+        /// Whether state-changing user messages are currently disabled.
+        paused: bool,
+        /// Usernames that only the owner can assign.
+        reserved: Mapping<Hash, ()>,
+        /// Registration prices keyed by name length tier.
+        length_prices: Mapping<u8, Balance>,
+        /// Pending offers of usernames to recipients that have yet to claim them.
+        offers: Mapping<Hash, AccountId>,
+        /// Operators approved to manage the records of usernames.
+        operators: Mapping<Hash, AccountId>,
+        /// Dense index of registered usernames, holding `registration_count` entries.
+        names: Mapping<u32, Hash>,
+        /// Position of each registered username within `names`.
+        name_positions: Mapping<Hash, u32>,
+        /// Sequential id assigned to each registration.
+        registration_ids: Mapping<Hash, u32>,
+        /// Id of the next registration; ids are never reused.
+        next_registration_id: u32,
+        /// Start of the current rate limit window and registrations within it per account.
+        rate_limits: Mapping<AccountId, (Timestamp, u32)>,
+        /// Length of a rate limit window.
+        rate_window: Timestamp,
+        /// Maximum number of registrations per account within a rate limit window.
+        max_per_window: u32,
+        /// Usernames frozen by the owner during an investigation.
+        frozen: Mapping<Hash, ()>,
+        /// Usernames held by each account, in a dense list per account.
+        owned_names: Mapping<(AccountId, u32), Hash>,
+        /// Number of usernames held by each account.
+        owned_count: Mapping<AccountId, u32>,
+        /// Position of each username within its holder's list in `owned_names`.
+        owned_positions: Mapping<Hash, u32>,
+        /// Accounts assigned to subnames, keyed by parent username and label.
+        subnames: Mapping<(Hash, Hash), AccountId>,
+        /// Registration id of the parent username each subname was created under.
+        subname_parents: Mapping<(Hash, Hash), u32>,
+        /// Accounts approved to transfer usernames on behalf of their owners.
+        approvals: Mapping<Hash, AccountId>,
+        /// Number of state changes of each username, never reset.
+        nonces: Mapping<Hash, u64>,
+        /// Number of signed registrations relayed for each account.
+        signing_nonces: Mapping<AccountId, u64>,
+        /// Accounts each account has blocked from its public key, keyed by (blocker, blocked).
+        blocklist: Mapping<(AccountId, AccountId), ()>,
+        /// Whether names can still be imported from a previous deployment.
+        migration_open: bool,
+        /// Accounts allowed to recover usernames to a new owner.
+        guardians: Mapping<Hash, AccountId>,
+        /// Value paid for registrations over the lifetime of the contract.
+        total_fees_collected: Balance,
+        /// Minimum time a username has to be registered before it can be released or transferred.
+        min_hold: Timestamp,
+        /// Rolling hash over all registrations and releases, see `state_root_hint`.
+        state_root: Hash,
+        /// Accounts resolving any subname of a username without an explicit subname.
+        wildcard_target: Mapping<Hash, AccountId>,
+        /// Registration id of the username each wildcard target was set under.
+        wildcard_parents: Mapping<Hash, u32>,
+        /// Maximum number of usernames a single account may hold.
+        max_names_per_account: u32,
+        /// Accounts allowed to register usernames on behalf of others.
+        registrars: Mapping<AccountId, ()>,
+        /// Number of changes to the records and ownership of each username, never reset.
+        meta_version: Mapping<Hash, u64>,
+        /// Asking prices of usernames listed for sale by their owners.
+        listings: Mapping<Hash, Balance>,
+        /// Running auctions of usernames.
+        auctions: Mapping<Hash, Auction>,
+        /// Set while a value-transferring message is executing. Kept in a mapping so that it
+        /// reaches storage before any outgoing call, whereas plain fields are only written
+        /// back once the message returns.
+        locked: Mapping<(), ()>,
+        /// Auction refunds owed to each account, pulled through `withdraw_refund`.
+        pending_withdrawals: Mapping<AccountId, Balance>,
+        /// Value held on behalf of bidders: running highest bids and pending refunds.
+        escrowed: Balance,
+        /// Content identifiers, such as IPFS CIDs, of the profile pages of usernames.
+        content_hash: Mapping<Hash, Vec<u8>>,
+        /// Keys of the text records set on each username, in a dense list per username.
+        text_keys: Mapping<(Hash, u32), Vec<u8>>,
+        /// Number of text records set on each username.
+        text_key_count: Mapping<Hash, u32>,
+        /// Position of each text record key within its username's list in `text_keys`.
+        text_key_positions: Mapping<(Hash, Vec<u8>), u32>,
+        /// Whether `Register` and `EditUsername` events are emitted.
+        emit_events: bool,
+        /// Characters accepted in plaintext usernames.
+        charset_policy: CharsetPolicy,
+        /// Lessee and end of the lease of each leased username.
+        leases: Mapping<Hash, (AccountId, Timestamp)>,
+        /// Block number of the most recent state change, see `last_mutation_block`.
+        last_mutation_block: BlockNumber,
+        /// Whether `StateChanged` events are emitted.
+        state_changed_events: bool,
+        /// Names each aliased username resolves through, see `set_alias`.
+        aliases: Mapping<Hash, Hash>,
+        /// Ring buffer of the last ownership changes of each username, see `owner_at`.
+        owner_history: Mapping<(Hash, u32), (BlockNumber, Option<AccountId>)>,
+        /// Number of ownership changes ever recorded for each username.
+        owner_history_len: Mapping<Hash, u32>,
     }
 
     /// Errors that can occur upon calling this contract.
@@ -55,126 +542,6426 @@ mod uke_human_dns {
         UsernameAlreadyExists,
         /// Returned if caller is not owner while required to.
         CallerIsNotOwner,
+        /// Returned if the name has not been registered.
+        UsernameNotFound,
+        /// Returned if caller is not the contract owner while required to.
+        NotAdmin,
+        /// Returned if the transferred value is below the registration fee.
+        InsufficientFee,
+        /// Returned if a transfer out of the contract fails.
+        TransferFailed,
+        /// Returned if the contract balance does not cover the requested amount.
+        InsufficientBalance,
+        /// Returned if the name has already expired.
+        NameExpired,
+        /// Returned if no matching commitment exists for the caller.
+        NoCommitment,
+        /// Returned if the commitment is younger than the minimum commitment age.
+        CommitmentTooNew,
+        /// Returned if a batch exceeds the maximum batch size.
+        BatchTooLarge,
+        /// Returned if a record key or value exceeds the maximum length.
+        ValueTooLong,
+        /// Returned if the contract is paused.
+        ContractPaused,
+        /// Returned if the name is reserved and cannot be registered publicly.
+        NameReserved,
+        /// Returned if caller is not the nominated owner while required to.
+        NotPendingOwner,
+        /// Returned if a plaintext username is shorter than the minimum length.
+        NameTooShort,
+        /// Returned if a plaintext username is longer than the maximum length.
+        NameTooLong,
+        /// Returned if a plaintext username contains a disallowed character.
+        InvalidCharacter,
+        /// Returned if the name has no pending offer.
+        NoPendingOffer,
+        /// Returned if caller is not the recipient of the pending offer.
+        NotOfferRecipient,
+        /// Returned if caller is neither the owner nor the operator of the name.
+        NotAuthorized,
+        /// Returned if the caller registered too many names within the rate limit window.
+        RateLimited,
+        /// Returned if the name is frozen.
+        NameFrozen,
+        /// Returned if the name is expired but still within its grace period.
+        InGracePeriod,
+        /// Returned if the coin type cannot be set as an address record.
+        InvalidCoinType,
+        /// Returned if an edit would leave a username unchanged.
+        NoChange,
+        /// Returned if the username hash is the all-zero default hash.
+        InvalidName,
+        /// Returned if a signature was not produced by the expected account.
+        BadSignature,
+        /// Returned if names are imported after the migration was closed.
+        MigrationClosed,
+        /// Returned if the contract code could not be replaced.
+        UpgradeFailed,
+        /// Returned if caller is not the guardian of the username.
+        NotGuardian,
+        /// Returned if the username has not been held for the minimum holding period.
+        MinHoldNotMet,
+        /// Returned if an account would hold more than the maximum number of usernames.
+        TooManyNames,
+        /// Returned if the username is not listed for sale.
+        NotForSale,
+        /// Returned if the transferred value is below the asking price.
+        InsufficientFunds,
+        /// Returned if the username is not up for auction.
+        NoAuction,
+        /// Returned if a bid arrives after the auction ended, or an auction ends in the past.
+        AuctionEnded,
+        /// Returned if a bid does not exceed the highest bid.
+        BidTooLow,
+        /// Returned if an auction is settled before it ended.
+        AuctionNotEnded,
+        /// Returned if an auction is started on a username that is already up for auction.
+        AuctionRunning,
+        /// Returned if a value-transferring message is entered again before it returned.
+        Reentrancy,
+        /// Returned if the caller has no refunds to withdraw.
+        NothingToWithdraw,
+        /// Returned if a record value is empty.
+        EmptyValue,
+        /// Returned if a username already holds the maximum number of text or address records.
+        TooManyRecords,
+        /// Returned if a lease would end before the current block.
+        InvalidLease,
+        /// Returned if the name is not leased.
+        NoLease,
+        /// Returned if a hash algorithm id is not one of the `ALGO_*` values.
+        UnsupportedAlgo,
+        /// Returned if an alias would lead back to the aliased name.
+        AliasLoop,
+        /// Returned if a registration has to go through `commit` and `reveal`.
+        CommitmentRequired,
+        /// Returned if a username would be moved to the zero account.
+        InvalidRecipient,
+    }
+
+    /// Lifecycle state of a username.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+    pub enum NameStatus {
+        /// Registered and within its TTL.
+        Active,
+        /// Past its TTL but within the grace period; still resolves but cannot be edited.
+        Grace,
+        /// Past its grace period; no longer resolves and can be reclaimed.
+        Expired,
+        /// Not registered.
+        Unregistered,
+    }
+
+    /// Characters accepted in plaintext usernames by `register_plain`.
+    ///
+    /// Letters are accepted in either case, since names are lowercased before hashing.
+    #[derive(
+        Debug,
+        Clone,
+        Copy,
+        Default,
+        PartialEq,
+        Eq,
+        scale::Encode,
+        scale::Decode,
+        SpreadLayout,
+        PackedLayout,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(::scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub enum CharsetPolicy {
+        /// ASCII letters only.
+        AsciiLower,
+        /// ASCII letters and digits.
+        AlphaNum,
+        /// ASCII letters, digits, `-` and `_`.
+        #[default]
+        AlphaNumDash,
+    }
+
+    impl SpreadAllocate for CharsetPolicy {
+        fn allocate_spread(ptr: &mut KeyPtr) -> Self {
+            ptr.advance_by(<Self as SpreadLayout>::FOOTPRINT);
+            Self::default()
+        }
+    }
+
+    impl CharsetPolicy {
+        /// Returns whether the policy accepts the given character.
+        fn allows(self, c: u8) -> bool {
+            match self {
+                Self::AsciiLower => c.is_ascii_alphabetic(),
+                Self::AlphaNum => c.is_ascii_alphanumeric(),
+                Self::AlphaNumDash => c.is_ascii_alphanumeric() || c == b'-' || c == b'_',
+            }
+        }
+    }
+
+    /// Summary of a username's registration, as returned by `info`.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+    pub struct RegistrationInfo {
+        /// Account controlling the username.
+        pub owner: AccountId,
+        /// Account the username resolves to.
+        pub target: AccountId,
+        /// Sequential id of the registration.
+        pub id: u32,
+        /// Block timestamp of the registration, unaffected by renewals.
+        pub registered_at: Timestamp,
+        /// Whether the username is the primary name of its owner.
+        pub is_primary: bool,
+        /// Whether the username has outlived its TTL, see `is_expired`.
+        pub expired: bool,
+    }
+
+    /// Settings of the contract, as returned by `config`.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+    pub struct Config {
+        /// Fee required to register a username.
+        pub fee: Balance,
+        /// Lifetime of a registration.
+        pub ttl: Timestamp,
+        /// Period after expiry during which a username still resolves.
+        pub grace_period: Timestamp,
+        /// Minimum age of a commitment before it can be revealed.
+        pub min_commitment_age: Timestamp,
+        /// Minimum time a username has to be held before it can be released or transferred.
+        pub min_hold: Timestamp,
+        /// Maximum number of entries accepted by batch messages.
+        pub max_batch: u32,
+        /// Maximum length of record keys and values.
+        pub max_text_len: u32,
+        /// Maximum number of usernames a single account may hold.
+        pub max_names_per_account: u32,
+        /// Length of the registration rate limit window.
+        pub rate_window: Timestamp,
+        /// Maximum number of registrations per account within a window.
+        pub max_per_window: u32,
+        /// Characters accepted in plaintext usernames.
+        pub charset_policy: CharsetPolicy,
+        /// Whether state-changing user messages are paused.
+        pub paused: bool,
     }
 
+    /// Running auction of a username, as returned by `auction_of`.
+    #[derive(
+        Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode, SpreadLayout, PackedLayout,
+    )]
+    #[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+    pub struct Auction {
+        /// Owner of the username when the auction started.
+        pub seller: AccountId,
+        /// Block timestamp from which no more bids are accepted.
+        pub end: Timestamp,
+        /// Account that placed the highest bid so far, if any.
+        pub highest_bidder: Option<AccountId>,
+        /// Highest bid so far, held by the contract until the auction is settled.
+        pub highest_bid: Balance,
+    }
+
+    /// Registration state of a username, kept in a single storage entry.
+    #[derive(
+        Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode, SpreadLayout, PackedLayout,
+    )]
+    #[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+    struct Record {
+        /// Account controlling the username, which may differ from its resolved address.
+        owner: AccountId,
+        /// Block timestamp at which the username was registered or last renewed.
+        registered_at: Timestamp,
+        /// Block timestamp at which the username was registered, kept across renewals.
+        created_at: Timestamp,
+        /// Bitfield of `FLAG_*` values.
+        flags: u8,
+        /// `ALGO_*` id of the hash function the username hash was computed with.
+        algo: u8,
+    }
+
+    /// Record flag set when the owner marked the resolved address as a contract.
+    const FLAG_CONTRACT_TARGET: u8 = 1;
+
+    /// Record flag set while the username has an entry in `leases`.
+    const FLAG_LEASED: u8 = 2;
+
+    /// Record flag set while the username has an entry in `aliases`.
+    const FLAG_ALIASED: u8 = 4;
+
+    /// `StateChanged` kind of a registration.
+    const KIND_REGISTER: u8 = 0;
+    /// `StateChanged` kind of a rename.
+    const KIND_EDIT: u8 = 1;
+    /// `StateChanged` kind of a transfer.
+    const KIND_TRANSFER: u8 = 2;
+    /// `StateChanged` kind of a release.
+    const KIND_RELEASE: u8 = 3;
+
+    /// Algorithm id of Blake2x256 over the lowercased plaintext, as used by `register_plain`.
+    const ALGO_BLAKE2X256: u8 = 0;
+    /// Algorithm id of SHA2x256 over the lowercased plaintext.
+    const ALGO_SHA2X256: u8 = 1;
+    /// Algorithm id of Keccak256 over the lowercased plaintext.
+    const ALGO_KECCAK256: u8 = 2;
+
     /// Type alias for the contract's result type.
     pub type Result<T> = core::result::Result<T, Error>;
 
+    /// Default maximum number of entries accepted by batch messages.
+    const DEFAULT_MAX_BATCH: u32 = 16;
+
+    /// Coin type of the native chain, resolved through the `AccountId` records.
+    const NATIVE_COIN_TYPE: u32 = 354;
+
+    /// Maximum length of an address record.
+    const MAX_ADDR_LEN: usize = 128;
+
+    /// Maximum number of address records per username.
+    const MAX_ADDR_RECORDS: u32 = 16;
+
+    /// Maximum length of a content hash record.
+    const MAX_CONTENT_HASH_LEN: usize = 64;
+
+    /// Maximum number of entries returned by paginated reads.
+    const MAX_PAGE_SIZE: u32 = 100;
+
+    /// Maximum number of aliases followed when resolving a username.
+    const MAX_ALIAS_DEPTH: u32 = 3;
+
+    /// Maximum number of aliases walked when checking a chain for loops.
+    const MAX_ALIAS_WALK: u32 = MAX_ALIAS_DEPTH + 4;
+
+    /// Number of ownership changes kept per username for `owner_at`.
+    const OWNER_HISTORY_DEPTH: u32 = 8;
+
+    /// Minimum length of a plaintext username.
+    const MIN_NAME_LEN: usize = 3;
+
+    /// Maximum length of a plaintext username.
+    const MAX_NAME_LEN: usize = 32;
+
+    /// Length tier shared by all names of this length or longer.
+    const MAX_LENGTH_TIER: u8 = 6;
+
+    /// Default maximum length of text record keys and values.
+    const DEFAULT_MAX_TEXT_LEN: u32 = 256;
+
+    /// Maximum number of text records per username.
+    const MAX_TEXT_RECORDS: u32 = 32;
+
+    /// Interface version of the contract, bumped on each feature release.
+    const VERSION: u32 = 2;
+
+    /// Feature id of text records (`set_text`/`get_text`).
+    const FEATURE_TEXT_RECORDS: u32 = 1;
+    /// Feature id of messaging public keys (`set_pubkey`/`get_pubkey`).
+    const FEATURE_PUBKEYS: u32 = 2;
+    /// Feature id of per-coin address records (`set_addr`/`get_addr`).
+    const FEATURE_ADDR_RECORDS: u32 = 3;
+    /// Feature id of commit-reveal registration (`commit`/`reveal`).
+    const FEATURE_COMMIT_REVEAL: u32 = 4;
+    /// Feature id of name operators and transfer offers.
+    const FEATURE_DELEGATION: u32 = 5;
+    /// Feature id of TTL based expiry with a grace period.
+    const FEATURE_EXPIRY: u32 = 6;
+    /// Feature id of subnames (`create_subname`).
+    const FEATURE_SUBNAMES: u32 = 7;
+    /// Feature id of single-name transfer approvals (`approve`/`transfer_name_from`).
+    const FEATURE_APPROVALS: u32 = 8;
+    /// Feature id of fixed-price listings (`list_for_sale`/`buy`).
+    const FEATURE_LISTINGS: u32 = 9;
+    /// Feature id of auctions (`start_auction`/`bid`/`settle_auction`).
+    const FEATURE_AUCTIONS: u32 = 10;
+    /// Feature id of time-limited leases (`lease`).
+    const FEATURE_LEASES: u32 = 11;
+    /// Feature id of aliases (`set_alias`).
+    const FEATURE_ALIASES: u32 = 12;
+    /// Feature id of content hashes (`set_content_hash`).
+    const FEATURE_CONTENT_HASH: u32 = 13;
+    /// Feature id of signed registration (`register_signed`).
+    const FEATURE_SIGNED_REGISTRATION: u32 = 14;
+    /// Feature id of recovery guardians (`set_guardian`).
+    const FEATURE_GUARDIANS: u32 = 15;
+    /// Feature id of delegated registrars (`add_registrar`).
+    const FEATURE_REGISTRARS: u32 = 16;
+
+    /// Features supported by this version of the contract.
+    const SUPPORTED_FEATURES: [u32; 16] = [
+        FEATURE_TEXT_RECORDS,
+        FEATURE_PUBKEYS,
+        FEATURE_ADDR_RECORDS,
+        FEATURE_COMMIT_REVEAL,
+        FEATURE_DELEGATION,
+        FEATURE_EXPIRY,
+        FEATURE_SUBNAMES,
+        FEATURE_APPROVALS,
+        FEATURE_LISTINGS,
+        FEATURE_AUCTIONS,
+        FEATURE_LEASES,
+        FEATURE_ALIASES,
+        FEATURE_CONTENT_HASH,
+        FEATURE_SIGNED_REGISTRATION,
+        FEATURE_GUARDIANS,
+        FEATURE_REGISTRARS,
+    ];
+
     impl UkeHumanDns {
         #[ink(constructor)]
 
         /// Creates a new human dns contract
+        #[allow(clippy::new_without_default)]
         pub fn new() -> Self {
+            Self::new_configured(0, Timestamp::MAX, u32::MAX)
+        }
+
+        /// Creates a new human dns contract with the given registration fee, registration
+        /// lifetime and maximum number of usernames per account.
+        #[ink(constructor)]
+        pub fn new_configured(fee: Balance, ttl: Timestamp, max_names_per_account: u32) -> Self {
             ink_lang::utils::initialize_contract(|contract: &mut Self| {
                 contract.default_address = Default::default();
+                contract.owner = Self::env().caller();
+                contract.registration_fee = fee;
+                contract.ttl = ttl;
+                contract.max_batch = DEFAULT_MAX_BATCH;
+                contract.max_text_len = DEFAULT_MAX_TEXT_LEN;
+                contract.max_per_window = u32::MAX;
+                contract.migration_open = true;
+                contract.max_names_per_account = max_names_per_account;
+                contract.emit_events = true;
             })
         }
 
-        /// Retrieves a username from an account id from the global mapping.
+        /// Returns the administrator of the contract.
         #[ink(message)]
-        pub fn get_address(&self, name: Hash) -> AccountId {
-            self.get_address_or_default(name)
+        pub fn owner(&self) -> AccountId {
+            self.owner
         }
 
-        /// Register a new username to the mapping.
+        /// Returns the account nominated to become the next owner, if any.
         #[ink(message)]
-        pub fn register(&mut self, name: Hash) -> Result<()> {
-            let caller = self.env().caller();
-            if self.username_to_id.contains(&name) {
-                return Err(Error::UsernameAlreadyExists);
-            }
-            self.username_to_id.insert(&name, &caller);
+        pub fn pending_owner(&self) -> Option<AccountId> {
+            self.pending_owner
+        }
 
-            self.env().emit_event(Register { name, from: caller });
+        /// Nominate a new owner, who has to accept before ownership changes.
+        #[ink(message)]
+        pub fn transfer_ownership(&mut self, new_owner: AccountId) -> Result<()> {
+            self.ensure_owner()?;
+            self.pending_owner = Some(new_owner);
+
+            self.env().emit_event(OwnershipTransferStarted {
+                previous_owner: self.owner,
+                new_owner,
+            });
+
+            self.mark_mutated();
+            Ok(())
+        }
+
+        /// Accept ownership of the contract as the nominated owner.
+        #[ink(message)]
+        pub fn accept_ownership(&mut self) -> Result<()> {
+            let caller = self.env().caller();
+            if self.pending_owner != Some(caller) {
+                return Err(Error::NotPendingOwner);
+            }
+
+            let previous_owner = self.owner;
+            self.owner = caller;
+            self.pending_owner = None;
+
+            self.env().emit_event(OwnershipTransferred {
+                previous_owner,
+                new_owner: caller,
+            });
+
+            self.mark_mutated();
+            Ok(())
+        }
+
+        /// Returns whether the contract is paused.
+        #[ink(message)]
+        pub fn is_paused(&self) -> bool {
+            self.paused
+        }
+
+        /// Pause all state-changing user messages for emergency maintenance.
+        ///
+        /// Read messages and owner configuration keep working while paused.
+        #[ink(message)]
+        pub fn pause(&mut self) -> Result<()> {
+            self.ensure_owner()?;
+            self.paused = true;
+
+            self.env().emit_event(Paused {
+                by: self.env().caller(),
+            });
+
+            self.mark_mutated();
+            Ok(())
+        }
+
+        /// Resume state-changing user messages after a pause.
+        #[ink(message)]
+        pub fn unpause(&mut self) -> Result<()> {
+            self.ensure_owner()?;
+            self.paused = false;
+
+            self.env().emit_event(Unpaused {
+                by: self.env().caller(),
+            });
+
+            self.mark_mutated();
+            Ok(())
+        }
+
+        /// Returns the current settings of the contract in a single read.
+        #[ink(message)]
+        pub fn config(&self) -> Config {
+            Config {
+                fee: self.registration_fee,
+                ttl: self.ttl,
+                grace_period: self.grace_period,
+                min_commitment_age: self.min_commitment_age,
+                min_hold: self.min_hold,
+                max_batch: self.max_batch,
+                max_text_len: self.max_text_len,
+                max_names_per_account: self.max_names_per_account,
+                rate_window: self.rate_window,
+                max_per_window: self.max_per_window,
+                charset_policy: self.charset_policy,
+                paused: self.paused,
+            }
+        }
+
+        /// Returns the interface version of this deployment.
+        #[ink(message)]
+        pub fn version(&self) -> u32 {
+            VERSION
+        }
+
+        /// Returns whether this deployment supports the given feature.
+        ///
+        /// Feature ids: 1 text records, 2 messaging public keys, 3 per-coin address
+        /// records, 4 commit-reveal registration, 5 operators and offers, 6 expiry,
+        /// 7 subnames, 8 approvals, 9 listings, 10 auctions, 11 leases, 12 aliases,
+        /// 13 content hashes, 14 signed registration, 15 guardians, 16 registrars.
+        #[ink(message)]
+        pub fn supports(&self, feature_id: u32) -> bool {
+            SUPPORTED_FEATURES.contains(&feature_id)
+        }
+
+        /// Returns the number of currently registered usernames.
+        #[ink(message)]
+        pub fn total_registrations(&self) -> u32 {
+            self.registration_count
+        }
+
+        /// Returns the usernames held by an account.
+        ///
+        /// The list is compacted on removal by moving the last name into the freed slot, so
+        /// names can change position over time. At most `MAX_PAGE_SIZE` names are returned.
+        #[ink(message)]
+        pub fn names_of(&self, who: AccountId) -> Vec<Hash> {
+            let count = self
+                .owned_count
+                .get(who)
+                .unwrap_or_default()
+                .min(MAX_PAGE_SIZE);
+            (0..count)
+                .filter_map(|i| self.owned_names.get((who, i)))
+                .collect()
+        }
+
+        /// Returns up to `limit` registered username hashes starting at index `start`.
+        ///
+        /// The index is compacted on release by moving the last entry into the freed slot, so
+        /// entries can change position between calls but never refer to released names.
+        /// Expired names stay listed until they are reclaimed or released. `limit` is capped
+        /// at `MAX_PAGE_SIZE`.
+        #[ink(message)]
+        pub fn names_paged(&self, start: u32, limit: u32) -> Vec<Hash> {
+            let end = start
+                .saturating_add(limit.min(MAX_PAGE_SIZE))
+                .min(self.registration_count);
+            (start..end).filter_map(|i| self.names.get(i)).collect()
+        }
+
+        /// Lists the usernames registered between `from` and `to`, both inclusive.
+        ///
+        /// Renewals do not move a username out of the window it was registered in.
+        /// Scans a page of at most `MAX_PAGE_SIZE` entries of the name index starting at
+        /// `start`, so a page may come back short or empty while later pages still match.
+        /// This is only meant for small deployments; indexing `Register` events scales
+        /// better for large ranges.
+        #[ink(message)]
+        pub fn registered_between(
+            &self,
+            from: Timestamp,
+            to: Timestamp,
+            start: u32,
+            limit: u32,
+        ) -> Vec<Hash> {
+            self.names_paged(start, limit)
+                .into_iter()
+                .filter(|name| {
+                    self.records
+                        .get(name)
+                        .is_some_and(|record| (from..=to).contains(&record.created_at))
+                })
+                .collect()
+        }
+
+        /// Returns the block number of the most recent successful state-changing message.
+        ///
+        /// Indexers restarting from scratch can start replaying events from this block
+        /// instead of from genesis. It is 0 until the first change after deployment.
+        #[ink(message)]
+        pub fn last_mutation_block(&self) -> BlockNumber {
+            self.last_mutation_block
+        }
+
+        /// Returns the number of registered usernames along with a rolling hash of the registry.
+        ///
+        /// The hash starts out as the default hash and is replaced by the Blake2x256 hash of
+        /// the SCALE encoded `(previous, name, registered)` on every registration (`true`)
+        /// and release (`false`) of a name, in the order of their `Register`, `Unregister`
+        /// and `AdminReleased` events. Renames do not change it, unless they replace an
+        /// expired name, which counts as a release of that name. Clients replaying events
+        /// can compare against it to detect missed events. This is a heuristic integrity
+        /// check, not a Merkle proof of the registry contents.
+        #[ink(message)]
+        pub fn state_root_hint(&self) -> (u32, Hash) {
+            (self.registration_count, self.state_root)
+        }
+
+        /// Retrieves a username from an account id from the global mapping.
+        #[ink(message)]
+        pub fn get_address(&self, name: Hash) -> AccountId {
+            self.get_address_or_default(name)
+        }
+
+        /// Retrieves the account a username resolves to, failing if it is unregistered or expired.
+        #[ink(message)]
+        pub fn try_get_address(&self, name: Hash) -> Result<AccountId> {
+            self.lookup(name).ok_or(Error::UsernameNotFound)
+        }
+
+        /// Resolves a username to its account along with the account's public key, if published.
+        ///
+        /// The key is withheld if the owner of the username blocked the caller.
+        #[ink(message)]
+        pub fn resolve_full(&self, name: Hash) -> Option<(AccountId, Option<[u8; 32]>)> {
+            let account = self.lookup(name)?;
+            let blocked = self
+                .lookup_owner(name)
+                .is_some_and(|owner| self.blocklist.contains((owner, self.env().caller())));
+            if blocked {
+                return Some((account, None));
+            }
+            Some((account, self.pubkeys.get(account)))
+        }
+
+        /// Resolves a username like `resolve`, leaving an on-chain trail of the lookup.
+        ///
+        /// Meant for metered or audited resolution: it emits a `Resolved` event naming the
+        /// caller, so unlike `resolve` it has to be submitted as a transaction and costs gas.
+        #[ink(message)]
+        pub fn resolve_logged(&mut self, name: Hash) -> Option<AccountId> {
+            let address = self.lookup(name);
+            self.env().emit_event(Resolved {
+                name,
+                by: self.env().caller(),
+            });
+            address
+        }
+
+        /// Resolves several usernames at once, in the order they were given.
+        ///
+        /// Only the first `MAX_PAGE_SIZE` names are resolved, the rest are dropped.
+        #[ink(message)]
+        pub fn resolve_batch(&self, names: Vec<Hash>) -> Vec<Option<AccountId>> {
+            names
+                .into_iter()
+                .take(MAX_PAGE_SIZE as usize)
+                .map(|name| self.lookup(name))
+                .collect()
+        }
+
+        /// Retrieves the usernames of several accounts at once, in the order they were given.
+        ///
+        /// Accounts holding several names get their primary name, see `primary_name_of`.
+        /// Only the first `MAX_PAGE_SIZE` accounts are looked up, the rest are dropped.
+        #[ink(message)]
+        pub fn get_name_batch(&self, accounts: Vec<AccountId>) -> Vec<Option<Hash>> {
+            accounts
+                .into_iter()
+                .take(MAX_PAGE_SIZE as usize)
+                .map(|who| self.primary_name_of(who))
+                .collect()
+        }
+
+        /// Returns whether a username could be registered publicly right now.
+        ///
+        /// This is the case if the username is unregistered or past its grace period, not
+        /// reserved, and not the default hash. Fees, rate limits and the per-account cap
+        /// depend on the caller and are not taken into account.
+        #[ink(message)]
+        pub fn is_available(&self, name: Hash) -> bool {
+            self.ensure_valid_hash(name).is_ok()
+                && self.ensure_not_reserved(name).is_ok()
+                && !self.is_taken(name)
+        }
+
+        /// Returns whether a username has a registration entry.
+        ///
+        /// Expired names that have not been reclaimed yet still exist.
+        #[ink(message)]
+        pub fn exists(&self, name: Hash) -> bool {
+            self.username_to_id.contains(name)
+        }
+
+        /// Returns the sequential id of a username's registration, if registered.
+        #[ink(message)]
+        pub fn registration_id_of(&self, name: Hash) -> Option<u32> {
+            self.registration_ids.get(name)
+        }
+
+        /// Returns the account controlling a username, if any.
+        ///
+        /// This may differ from the address the name resolves to. Intended for the
+        /// registration-collision path: when `register` fails with `UsernameAlreadyExists`,
+        /// clients can show who holds the name.
+        #[ink(message)]
+        pub fn owner_of(&self, name: Hash) -> Option<AccountId> {
+            self.lookup_owner(name)
+        }
+
+        /// Returns whether `claimed` currently controls the given username.
+        ///
+        /// Meant for off-chain ownership proofs: a client signs a challenge from its peer
+        /// with the key of `claimed`, and the peer checks the signature locally before
+        /// calling this to confirm that account still controls the name.
+        #[ink(message)]
+        pub fn verify_owner(&self, name: Hash, claimed: AccountId) -> bool {
+            self.lookup_owner(name) == Some(claimed)
+        }
+
+        /// Returns whether `who` owns the given username and its TTL has not run out.
+        ///
+        /// Unlike `verify_owner`, a name in its grace period does not count as active. Both
+        /// checks are made against the same block, so relayers can rely on a single call.
+        #[ink(message)]
+        pub fn is_active_owner(&self, name: Hash, who: AccountId) -> bool {
+            self.records.get(name).is_some_and(|record| {
+                record.owner == who && self.record_status(&record) == NameStatus::Active
+            })
+        }
+
+        /// Returns whether a registered username has outlived its TTL, including its grace period.
+        #[ink(message)]
+        pub fn is_expired(&self, name: Hash) -> bool {
+            matches!(
+                self.name_status(name),
+                NameStatus::Grace | NameStatus::Expired
+            )
+        }
+
+        /// Returns a summary of a username's registration, or `None` if it is not registered.
+        #[ink(message)]
+        pub fn info(&self, name: Hash) -> Option<RegistrationInfo> {
+            let Record {
+                owner, created_at, ..
+            } = self.records.get(name)?;
+            Some(RegistrationInfo {
+                owner,
+                target: self.username_to_id.get(name).unwrap_or(owner),
+                id: self.registration_ids.get(name).unwrap_or_default(),
+                registered_at: created_at,
+                is_primary: self.primary_name_of(owner) == Some(name),
+                expired: self.is_expired(name),
+            })
+        }
+
+        /// Returns the owner of a username as of the end of the given block.
+        ///
+        /// Only the last `OWNER_HISTORY_DEPTH` registrations, transfers, renames and releases
+        /// of each username are kept, so blocks before the oldest of them give `None`, as do
+        /// blocks before the username was first registered. Renames end the ownership of the
+        /// old hash. Expiry is not taken into account.
+        #[ink(message)]
+        pub fn owner_at(&self, name: Hash, block: BlockNumber) -> Option<AccountId> {
+            let len = self.owner_history_len.get(name).unwrap_or_default();
+            (len.saturating_sub(OWNER_HISTORY_DEPTH)..len)
+                .rev()
+                .filter_map(|i| self.owner_history.get((name, i % OWNER_HISTORY_DEPTH)))
+                .find(|(at, _)| *at <= block)
+                .and_then(|(_, owner)| owner)
+        }
+
+        /// Returns where a username stands in its registration lifecycle.
+        #[ink(message)]
+        pub fn name_status(&self, name: Hash) -> NameStatus {
+            match self.records.get(name) {
+                Some(record) => self.record_status(&record),
+                None => NameStatus::Unregistered,
+            }
+        }
+
+        /// Returns where a registered username stands in its registration lifecycle.
+        fn record_status(&self, record: &Record) -> NameStatus {
+            let expiry = record.registered_at.saturating_add(self.ttl);
+            let now = self.env().block_timestamp();
+            if now <= expiry {
+                NameStatus::Active
+            } else if now <= expiry.saturating_add(self.grace_period) {
+                NameStatus::Grace
+            } else {
+                NameStatus::Expired
+            }
+        }
+
+        /// Returns the grace period following the expiry of a registration.
+        #[ink(message)]
+        pub fn grace_period(&self) -> Timestamp {
+            self.grace_period
+        }
+
+        /// Set the grace period following the expiry of a registration.
+        #[ink(message)]
+        pub fn set_grace_period(&mut self, grace_period: Timestamp) -> Result<()> {
+            self.ensure_owner()?;
+            self.grace_period = grace_period;
+            self.mark_mutated();
+            Ok(())
+        }
+
+        /// Returns the time left before a username expires, or `None` if it is unknown or expired.
+        #[ink(message)]
+        pub fn time_remaining(&self, name: Hash) -> Option<Timestamp> {
+            let expiry = self
+                .records
+                .get(name)?
+                .registered_at
+                .saturating_add(self.ttl);
+            expiry.checked_sub(self.env().block_timestamp())
+        }
+
+        /// Returns the lifetime of a registration.
+        #[ink(message)]
+        pub fn ttl(&self) -> Timestamp {
+            self.ttl
+        }
+
+        /// Set the lifetime of a registration.
+        #[ink(message)]
+        pub fn set_ttl(&mut self, ttl: Timestamp) -> Result<()> {
+            self.ensure_owner()?;
+            self.ttl = ttl;
+            self.mark_mutated();
+            Ok(())
+        }
+
+        /// Register a new username to the mapping.
+        ///
+        /// The transferred value must cover `hash_only_price` and stays in the contract.
+        /// Expired usernames are available again and get reclaimed from their previous owner.
+        /// Returns the registration id of the username, as also carried by `Register`.
+        #[ink(message, payable)]
+        pub fn register(&mut self, name: Hash) -> Result<u32> {
+            self.ensure_not_paused()?;
+            self.ensure_commit_reveal_off()?;
+            let caller = self.env().caller();
+            self.ensure_fee_paid(1)?;
+            self.register_public(name, caller)
+        }
+
+        /// Register a new username whose hash was computed with the given algorithm.
+        ///
+        /// Supported ids are `ALGO_BLAKE2X256` (0), `ALGO_SHA2X256` (1) and `ALGO_KECCAK256`
+        /// (2), each over the lowercased plaintext. `register` records `ALGO_BLAKE2X256`.
+        /// The id is only recorded for clients to check with `algo_of`; it does not change
+        /// how the username resolves. Renames keep the id of the original registration.
+        #[ink(message, payable)]
+        pub fn register_with_algo(&mut self, name: Hash, algo: u8) -> Result<u32> {
+            self.ensure_not_paused()?;
+            self.ensure_commit_reveal_off()?;
+            if ![ALGO_BLAKE2X256, ALGO_SHA2X256, ALGO_KECCAK256].contains(&algo) {
+                return Err(Error::UnsupportedAlgo);
+            }
+            let caller = self.env().caller();
+            self.ensure_fee_paid(1)?;
+            let id = self.register_public(name, caller)?;
+            if let Some(record) = self.records.get(name) {
+                self.records.insert(name, &Record { algo, ..record });
+            }
+            Ok(id)
+        }
+
+        /// Returns the `ALGO_*` id of the hash function a registered username was hashed with.
+        #[ink(message)]
+        pub fn algo_of(&self, name: Hash) -> Option<u8> {
+            self.records.get(name).map(|record| record.algo)
+        }
+
+        /// Register a new username to another account, paid for by the caller.
+        ///
+        /// Only callable by the contract owner and approved registrars.
+        #[ink(message, payable)]
+        pub fn register_for(&mut self, name: Hash, owner: AccountId) -> Result<()> {
+            self.ensure_not_paused()?;
+            self.ensure_registrar()?;
+            self.ensure_fee_paid(1)?;
+            self.register_public(name, owner)?;
+            Ok(())
+        }
+
+        /// Register a new username priced by the length of its plaintext.
+        ///
+        /// All other registrations that only send the hash pay `hash_only_price` instead.
+        /// Since only the hash is sent on-chain, clients must pass the plaintext length
+        /// honestly; the contract cannot verify it.
+        #[ink(message, payable)]
+        pub fn register_with_len(&mut self, name: Hash, len: u8) -> Result<()> {
+            self.ensure_not_paused()?;
+            self.ensure_commit_reveal_off()?;
+            let caller = self.env().caller();
+            if self.env().transferred_value() < self.price_for_len(len) {
+                return Err(Error::InsufficientFee);
+            }
+            self.register_public(name, caller)?;
+            Ok(())
+        }
+
+        /// Register a new username from its plaintext, validating and hashing it on-chain.
+        ///
+        /// Names must be between `MIN_NAME_LEN` and `MAX_NAME_LEN` bytes of characters allowed
+        /// by the charset policy, and are priced by their actual length. Letters are lowercased
+        /// before hashing, so names differing only in case collide.
+        #[ink(message, payable)]
+        pub fn register_plain(&mut self, name: Vec<u8>) -> Result<()> {
+            self.ensure_not_paused()?;
+            self.ensure_commit_reveal_off()?;
+            let caller = self.env().caller();
+            self.validate_name(&name)?;
+            if self.env().transferred_value() < self.price_for_len(name.len() as u8) {
+                return Err(Error::InsufficientFee);
+            }
+
+            let name = self.name_hash(name);
+            self.register_public(name, caller)?;
+            Ok(())
+        }
+
+        /// Returns the characters accepted in plaintext usernames by `register_plain`.
+        #[ink(message)]
+        pub fn charset_policy(&self) -> CharsetPolicy {
+            self.charset_policy
+        }
+
+        /// Set the characters accepted in plaintext usernames by `register_plain`.
+        ///
+        /// Names registered under a previous policy are kept.
+        #[ink(message)]
+        pub fn set_charset_policy(&mut self, policy: CharsetPolicy) -> Result<()> {
+            self.ensure_owner()?;
+            self.charset_policy = policy;
+            self.mark_mutated();
+            Ok(())
+        }
+
+        /// Computes the canonical hash of a plaintext username as used by `register_plain`.
+        ///
+        /// Clients can dry-run this to get the hash to pass to `register` and `resolve`
+        /// instead of replicating the normalization. It does not check the charset policy.
+        #[ink(message)]
+        pub fn name_hash(&self, name: Vec<u8>) -> Hash {
+            Hash::from(self.env().hash_bytes::<Blake2x256>(&Self::normalize(&name)))
+        }
+
+        /// Register several new usernames to the caller at once.
+        ///
+        /// Either all usernames get registered or none, and the transferred value must cover
+        /// `hash_only_price` for each of them.
+        #[ink(message, payable)]
+        pub fn register_many(&mut self, names: Vec<Hash>) -> Result<()> {
+            self.ensure_not_paused()?;
+            self.ensure_commit_reveal_off()?;
+            let caller = self.env().caller();
+            self.ensure_batch_size(names.len())?;
+            self.ensure_fee_paid(names.len() as u32)?;
+
+            for (i, name) in names.iter().enumerate() {
+                self.ensure_valid_hash(*name)?;
+                self.ensure_not_reserved(*name)?;
+                if self.is_taken(*name) || names[..i].contains(name) {
+                    return Err(Error::UsernameAlreadyExists);
+                }
+            }
+            self.ensure_can_hold(caller, names.len() as u32)?;
+            let window = self.check_rate_limit(caller, names.len() as u32)?;
+            for name in names {
+                self.register_to(name, caller)?;
+            }
+            self.rate_limits.insert(caller, &window);
+            self.migration_open = false;
+            self.collect_fee();
+
+            Ok(())
+        }
+
+        /// Commit to a future registration without revealing the username.
+        ///
+        /// The commitment is the hash computed by `make_commitment` for the caller.
+        #[ink(message)]
+        pub fn commit(&mut self, commitment: Hash) -> Result<()> {
+            self.ensure_not_paused()?;
+            let caller = self.env().caller();
+            self.commitments
+                .insert((caller, commitment), &self.env().block_timestamp());
+            self.mark_mutated();
+            Ok(())
+        }
+
+        /// Register a username previously committed to by the caller.
+        ///
+        /// The commitment must be at least `min_commitment_age` old, which keeps pending
+        /// registrations from being front-run.
+        #[ink(message, payable)]
+        pub fn reveal(&mut self, name: Hash, secret: [u8; 32]) -> Result<()> {
+            self.ensure_not_paused()?;
+            let caller = self.env().caller();
+            self.ensure_fee_paid(1)?;
+
+            let commitment = self.make_commitment(name, secret, caller);
+            let committed_at = self
+                .commitments
+                .get((caller, commitment))
+                .ok_or(Error::NoCommitment)?;
+            if self.env().block_timestamp() < committed_at.saturating_add(self.min_commitment_age) {
+                return Err(Error::CommitmentTooNew);
+            }
+
+            self.register_public(name, caller)?;
+            self.commitments.remove((caller, commitment));
+
+            Ok(())
+        }
+
+        /// Register a username to `owner` on their behalf, paid for by the caller.
+        ///
+        /// `signature` is the ECDSA signature of `owner` over the hash returned by
+        /// `make_signed_registration`, which includes the current signing nonce of `owner`
+        /// so that the signature can only be used once.
+        #[ink(message, payable)]
+        pub fn register_signed(
+            &mut self,
+            name: Hash,
+            owner: AccountId,
+            signature: [u8; 65],
+        ) -> Result<()> {
+            self.ensure_not_paused()?;
+            self.ensure_commit_reveal_off()?;
+            self.ensure_fee_paid(1)?;
+
+            let message = self.signed_registration_message(name, owner);
+            let pubkey = self
+                .env()
+                .ecdsa_recover(&signature, &message)
+                .map_err(|_| Error::BadSignature)?;
+            let signer = AccountId::from(self.env().hash_bytes::<Blake2x256>(&pubkey));
+            if signer != owner {
+                return Err(Error::BadSignature);
+            }
+
+            self.register_public(name, owner)?;
+            let nonce = self.signing_nonce_of(owner);
+            self.signing_nonces.insert(owner, &(nonce + 1));
+
+            Ok(())
+        }
+
+        /// Returns the signing nonce of an account, bound into its signed registrations.
+        #[ink(message)]
+        pub fn signing_nonce_of(&self, who: AccountId) -> u64 {
+            self.signing_nonces.get(who).unwrap_or_default()
+        }
+
+        /// Computes the hash an account signs to have a username registered on its behalf.
+        #[ink(message)]
+        pub fn make_signed_registration(&self, name: Hash, owner: AccountId) -> Hash {
+            Hash::from(self.signed_registration_message(name, owner))
+        }
+
+        /// Computes the commitment hash binding a username and secret to an account.
+        #[ink(message)]
+        pub fn make_commitment(&self, name: Hash, secret: [u8; 32], who: AccountId) -> Hash {
+            Hash::from(
+                self.env()
+                    .hash_encoded::<Blake2x256, _>(&(name, secret, who)),
+            )
+        }
+
+        /// Returns the minimum age of a commitment before it can be revealed.
+        #[ink(message)]
+        pub fn min_commitment_age(&self) -> Timestamp {
+            self.min_commitment_age
+        }
+
+        /// Set the minimum age of a commitment before it can be revealed.
+        ///
+        /// A non-zero age enables commit-reveal: public registrations then have to go through
+        /// `commit` and `reveal`, and the other public registration messages fail with
+        /// `CommitmentRequired`, as they would let anyone front-run a pending `reveal`.
+        /// `register_for`, `assign_reserved` and imports are unaffected.
+        #[ink(message)]
+        pub fn set_min_commitment_age(&mut self, age: Timestamp) -> Result<()> {
+            self.ensure_owner()?;
+            self.min_commitment_age = age;
+            self.mark_mutated();
+            Ok(())
+        }
+
+        /// Returns the minimum time a username has to be registered before it can be released
+        /// or change hands.
+        ///
+        /// This covers transfers, claimed offers, sales and auctions. Guardian recoveries are
+        /// exempt, as they restore a name to an owner who lost access to it.
+        #[ink(message)]
+        pub fn min_hold(&self) -> Timestamp {
+            self.min_hold
+        }
+
+        /// Set the minimum holding period of usernames.
+        ///
+        /// The period counts from registration, so neither transfers nor renewals restart it.
+        #[ink(message)]
+        pub fn set_min_hold(&mut self, min_hold: Timestamp) -> Result<()> {
+            self.ensure_owner()?;
+            self.min_hold = min_hold;
+            self.mark_mutated();
+            Ok(())
+        }
+
+        /// Edit an existing username.
+        #[ink(message)]
+        pub fn edit_username(&mut self, old_name: Hash, new_name: Hash) -> Result<()> {
+            self.ensure_not_paused()?;
+            let caller = self.env().caller();
+            self.ensure_can_rename(old_name, new_name, caller)?;
+
+            self.rename(old_name, new_name, caller);
+
+            Ok(())
+        }
+
+        /// Edit several owned usernames at once.
+        ///
+        /// Either all usernames get renamed or none. Every old name must be owned by the
+        /// caller and no new name may already be taken.
+        #[ink(message)]
+        pub fn edit_many(&mut self, pairs: Vec<(Hash, Hash)>) -> Result<()> {
+            self.ensure_not_paused()?;
+            let caller = self.env().caller();
+            self.ensure_batch_size(pairs.len())?;
+
+            for (i, (old_name, new_name)) in pairs.iter().enumerate() {
+                self.ensure_can_rename(*old_name, *new_name, caller)?;
+                if pairs[..i].iter().any(|(old, _)| old == old_name) {
+                    return Err(Error::CallerIsNotOwner);
+                }
+                if pairs[..i].iter().any(|(_, new)| new == new_name) {
+                    return Err(Error::UsernameAlreadyExists);
+                }
+            }
+            for (old_name, new_name) in pairs {
+                self.rename(old_name, new_name, caller);
+            }
+
+            Ok(())
+        }
+
+        /// Unregister an owned username, freeing it up for registration.
+        #[ink(message)]
+        pub fn unregister(&mut self, name: Hash) -> Result<()> {
+            self.ensure_not_paused()?;
+            let caller = self.ensure_name_owner(name)?;
+            self.ensure_not_frozen(name)?;
+            self.ensure_min_hold(name)?;
+
+            self.release_name(name, caller);
+
+            self.env().emit_event(Unregister { name, from: caller });
+
+            Ok(())
+        }
+
+        /// Transfer an owned username to another account.
+        ///
+        /// Transferring a name to its current owner succeeds without changing anything.
+        /// Transferring it to the zero account unregisters it instead, as no one controls
+        /// that account.
+        #[ink(message)]
+        pub fn transfer_name(&mut self, name: Hash, to: AccountId) -> Result<()> {
+            self.ensure_not_paused()?;
+            let caller = self.ensure_name_owner(name)?;
+            self.ensure_not_frozen(name)?;
+            self.ensure_not_in_grace(name)?;
+            self.ensure_min_hold(name)?;
+            if to == caller {
+                return Ok(());
+            }
+            if to == AccountId::default() {
+                self.release_name(name, caller);
+                self.env().emit_event(Unregister { name, from: caller });
+                return Ok(());
+            }
+
+            self.move_name(name, caller, to)?;
+
+            self.env().emit_event(NameTransferred {
+                name,
+                from: caller,
+                to,
+            });
 
             Ok(())
         }
 
-        /// Edit an existing username.
-        #[ink(message)]
-        pub fn edit_username(&mut self, old_name: Hash, new_name: Hash) -> Result<()> {
-            let caller = self.env().caller();
+        /// Point an owned username at a different address, such as a cold wallet or a contract.
+        ///
+        /// Ownership of the username stays with the caller. Any contract mark set through
+        /// `set_contract_target` is cleared.
+        #[ink(message)]
+        pub fn set_address(&mut self, name: Hash, target: AccountId) -> Result<()> {
+            self.ensure_not_paused()?;
+            self.ensure_name_owner(name)?;
+            self.ensure_not_frozen(name)?;
+            let old_target = self.username_to_id.get(name).unwrap_or_default();
+            self.username_to_id.insert(name, &target);
+            self.set_record_flag(name, FLAG_CONTRACT_TARGET, false);
+            self.bump_nonce(name);
+            self.bump_meta_version(name);
+
+            self.env().emit_event(ResolutionChanged {
+                name,
+                old_target,
+                new_target: target,
+            });
+
+            Ok(())
+        }
+
+        /// Lend an owned username to another account until the given block timestamp.
+        ///
+        /// The username resolves to the lessee while `now < until` and to its regular address
+        /// from `until` on, while ownership stays with the caller throughout. A new lease
+        /// replaces the previous one. Leases are dropped when the username gets transferred
+        /// or released, and carried over on renames.
+        #[ink(message)]
+        pub fn lease(&mut self, name: Hash, to: AccountId, until: Timestamp) -> Result<()> {
+            self.ensure_not_paused()?;
+            let lessor = self.ensure_name_owner(name)?;
+            self.ensure_not_frozen(name)?;
+            if until <= self.env().block_timestamp() {
+                return Err(Error::InvalidLease);
+            }
+
+            self.leases.insert(name, &(to, until));
+            self.set_record_flag(name, FLAG_LEASED, true);
+            self.bump_nonce(name);
+            self.bump_meta_version(name);
+
+            self.env().emit_event(Leased {
+                name,
+                lessor,
+                lessee: to,
+                until,
+            });
+
+            Ok(())
+        }
+
+        /// End the lease of an owned username, including leases that already ran out.
+        #[ink(message)]
+        pub fn end_lease(&mut self, name: Hash) -> Result<()> {
+            self.ensure_not_paused()?;
+            self.ensure_name_owner(name)?;
+            let (lessee, _) = self.leases.get(name).ok_or(Error::NoLease)?;
+
+            self.leases.remove(name);
+            self.set_record_flag(name, FLAG_LEASED, false);
+            self.bump_nonce(name);
+            self.bump_meta_version(name);
+
+            self.env().emit_event(LeaseEnded { name, lessee });
+
+            Ok(())
+        }
+
+        /// Returns the lessee and end of the running lease of a username, if any.
+        #[ink(message)]
+        pub fn lease_of(&self, name: Hash) -> Option<(AccountId, Timestamp)> {
+            self.leases
+                .get(name)
+                .filter(|(_, until)| self.env().block_timestamp() < *until)
+        }
+
+        /// Make an owned username resolve to whatever another owned username resolves to.
+        ///
+        /// Aliases are followed for up to `MAX_ALIAS_DEPTH` hops; longer chains do not resolve.
+        /// Fails with `AliasLoop` if the chain from `target_name` leads back to `name` or is
+        /// longer than `MAX_ALIAS_WALK` hops. The alias is dropped when `name` gets
+        /// transferred or released, and carried over on renames unless that would close a
+        /// loop. Renaming or releasing `target_name` leaves the alias dangling, and while
+        /// `target_name` is held by another account, after a transfer, sale, recovery or
+        /// reclaim, `name` does not resolve at all.
+        #[ink(message)]
+        pub fn set_alias(&mut self, name: Hash, target_name: Hash) -> Result<()> {
+            self.ensure_not_paused()?;
+            self.ensure_name_owner(name)?;
+            self.ensure_name_owner(target_name)?;
+            self.ensure_not_frozen(name)?;
+            if self.alias_leads_to(target_name, name) {
+                return Err(Error::AliasLoop);
+            }
+
+            self.aliases.insert(name, &target_name);
+            self.set_record_flag(name, FLAG_ALIASED, true);
+            self.bump_nonce(name);
+            self.bump_meta_version(name);
+
+            self.env().emit_event(AliasChanged {
+                name,
+                target_name: Some(target_name),
+            });
+
+            Ok(())
+        }
+
+        /// Remove the alias of an owned username, so it resolves to its own address again.
+        #[ink(message)]
+        pub fn remove_alias(&mut self, name: Hash) -> Result<()> {
+            self.ensure_not_paused()?;
+            self.ensure_name_owner(name)?;
+            if !self.aliases.contains(name) {
+                return Ok(());
+            }
+
+            self.aliases.remove(name);
+            self.set_record_flag(name, FLAG_ALIASED, false);
+            self.bump_nonce(name);
+            self.bump_meta_version(name);
+
+            self.env().emit_event(AliasChanged {
+                name,
+                target_name: None,
+            });
+
+            Ok(())
+        }
+
+        /// Retrieves the name a username is aliased to, if any.
+        #[ink(message)]
+        pub fn alias_of(&self, name: Hash) -> Option<Hash> {
+            self.aliases.get(name)
+        }
+
+        /// Mark whether the address an owned username resolves to is a contract.
+        ///
+        /// The mark is advisory and does not affect resolution. Clients can use it to skip
+        /// messaging handshakes with contracts. It is cleared whenever the address changes.
+        #[ink(message)]
+        pub fn set_contract_target(&mut self, name: Hash, is_contract: bool) -> Result<()> {
+            self.ensure_not_paused()?;
+            self.ensure_name_owner(name)?;
+
+            self.set_record_flag(name, FLAG_CONTRACT_TARGET, is_contract);
+            self.bump_meta_version(name);
+
+            Ok(())
+        }
+
+        /// Returns whether the owner marked the address a username resolves to as a contract.
+        #[ink(message)]
+        pub fn target_is_contract(&self, name: Hash) -> bool {
+            self.records
+                .get(name)
+                .is_some_and(|record| record.flags & FLAG_CONTRACT_TARGET != 0)
+        }
+
+        /// Offer an owned username to another account, which has to claim it.
+        ///
+        /// The offer is dropped when the username gets renamed, transferred or unregistered.
+        #[ink(message)]
+        pub fn offer_name(&mut self, name: Hash, to: AccountId) -> Result<()> {
+            self.ensure_not_paused()?;
+            let caller = self.ensure_name_owner(name)?;
+            self.offers.insert(name, &to);
+
+            self.env().emit_event(NameOffered {
+                name,
+                from: caller,
+                to,
+            });
+
+            self.mark_mutated();
+            Ok(())
+        }
+
+        /// Claim a username offered to the caller.
+        #[ink(message)]
+        pub fn claim_name(&mut self, name: Hash) -> Result<()> {
+            self.ensure_not_paused()?;
+            let caller = self.env().caller();
+            match self.offers.get(name) {
+                None => return Err(Error::NoPendingOffer),
+                Some(to) if to != caller => return Err(Error::NotOfferRecipient),
+                Some(_) => {}
+            }
+            self.ensure_not_frozen(name)?;
+            self.ensure_not_in_grace(name)?;
+            self.ensure_min_hold(name)?;
+            let from = self.lookup_owner(name).ok_or(Error::UsernameNotFound)?;
+
+            self.move_name(name, from, caller)?;
+
+            self.env().emit_event(NameClaimed {
+                name,
+                from,
+                to: caller,
+            });
+
+            Ok(())
+        }
+
+        /// List an owned username for sale at the given price.
+        ///
+        /// The listing is dropped when the username gets renamed, transferred or unregistered.
+        #[ink(message)]
+        pub fn list_for_sale(&mut self, name: Hash, price: Balance) -> Result<()> {
+            self.ensure_not_paused()?;
+            self.ensure_name_owner(name)?;
+            self.ensure_not_frozen(name)?;
+
+            self.listings.insert(name, &price);
+
+            self.mark_mutated();
+            Ok(())
+        }
+
+        /// Withdraw an owned username from sale.
+        #[ink(message)]
+        pub fn cancel_listing(&mut self, name: Hash) -> Result<()> {
+            self.ensure_not_paused()?;
+            self.ensure_name_owner(name)?;
+            if !self.listings.contains(name) {
+                return Err(Error::NotForSale);
+            }
+
+            self.listings.remove(name);
+
+            self.mark_mutated();
+            Ok(())
+        }
+
+        /// Retrieves the asking price of a username, if it is listed for sale.
+        #[ink(message)]
+        pub fn listing_of(&self, name: Hash) -> Option<Balance> {
+            self.listings.get(name)
+        }
+
+        /// Buy a listed username, paying the transferred value to its owner.
+        #[ink(message, payable)]
+        pub fn buy(&mut self, name: Hash) -> Result<()> {
+            self.ensure_not_paused()?;
+            let caller = self.env().caller();
+            let price = self.listings.get(name).ok_or(Error::NotForSale)?;
+            let seller = self.lookup_owner(name).ok_or(Error::UsernameNotFound)?;
+            let paid = self.env().transferred_value();
+            if paid < price {
+                return Err(Error::InsufficientFunds);
+            }
+            self.ensure_not_frozen(name)?;
+            self.ensure_not_in_grace(name)?;
+            self.ensure_min_hold(name)?;
+
+            self.non_reentrant(|this| {
+                this.move_name(name, seller, caller)?;
+                this.env()
+                    .transfer(seller, paid)
+                    .map_err(|_| Error::TransferFailed)?;
+
+                this.env().emit_event(NameSold {
+                    name,
+                    from: seller,
+                    to: caller,
+                    price: paid,
+                });
+
+                Ok(())
+            })
+        }
+
+        /// Put an owned username up for auction until the given block timestamp.
+        ///
+        /// Bids are public and each one has to exceed the previous highest bid, whose bidder
+        /// can then pull it back through `withdraw_refund`. The username stays with the seller
+        /// until the auction is settled.
+        #[ink(message)]
+        pub fn start_auction(&mut self, name: Hash, end: Timestamp) -> Result<()> {
+            self.ensure_not_paused()?;
+            let caller = self.ensure_name_owner(name)?;
+            self.ensure_not_frozen(name)?;
+            self.ensure_min_hold(name)?;
+            if self.auctions.contains(name) {
+                return Err(Error::AuctionRunning);
+            }
+            if end <= self.env().block_timestamp() {
+                return Err(Error::AuctionEnded);
+            }
+
+            self.auctions.insert(
+                name,
+                &Auction {
+                    seller: caller,
+                    end,
+                    highest_bidder: None,
+                    highest_bid: 0,
+                },
+            );
+
+            self.env().emit_event(AuctionStarted {
+                name,
+                seller: caller,
+                end,
+            });
+
+            self.mark_mutated();
+            Ok(())
+        }
+
+        /// Retrieves the running auction of a username, if any.
+        #[ink(message)]
+        pub fn auction_of(&self, name: Hash) -> Option<Auction> {
+            self.auctions.get(name)
+        }
+
+        /// Bid the transferred value on an auctioned username.
+        #[ink(message, payable)]
+        pub fn bid(&mut self, name: Hash) -> Result<()> {
+            self.ensure_not_paused()?;
+            let caller = self.env().caller();
+            let mut auction = self.auctions.get(name).ok_or(Error::NoAuction)?;
+            if self.env().block_timestamp() >= auction.end {
+                return Err(Error::AuctionEnded);
+            }
+            let amount = self.env().transferred_value();
+            if amount <= auction.highest_bid {
+                return Err(Error::BidTooLow);
+            }
+
+            let previous = auction.highest_bidder.replace(caller);
+            let refund = core::mem::replace(&mut auction.highest_bid, amount);
+            self.auctions.insert(name, &auction);
+            if let Some(previous) = previous {
+                self.credit_refund(previous, refund);
+            }
+            self.escrowed = self.escrowed.saturating_add(amount);
+
+            self.env().emit_event(BidPlaced {
+                name,
+                bidder: caller,
+                amount,
+            });
+
+            self.mark_mutated();
+            Ok(())
+        }
+
+        /// Settle an ended auction, moving the username to the highest bidder and paying the
+        /// seller.
+        ///
+        /// Callable by anyone. If nobody bid, the seller no longer holds the username, its
+        /// minimum holding period is not over yet or the winner cannot take on another name,
+        /// the highest bid is credited back to its bidder instead.
+        #[ink(message)]
+        pub fn settle_auction(&mut self, name: Hash) -> Result<()> {
+            self.ensure_not_paused()?;
+            let auction = self.auctions.get(name).ok_or(Error::NoAuction)?;
+            if self.env().block_timestamp() < auction.end {
+                return Err(Error::AuctionNotEnded);
+            }
+            let winner = auction.highest_bidder.filter(|winner| {
+                self.lookup_owner(name) == Some(auction.seller)
+                    && self.ensure_not_frozen(name).is_ok()
+                    && self.ensure_min_hold(name).is_ok()
+                    && self.ensure_can_hold(*winner, 1).is_ok()
+            });
+
+            self.non_reentrant(|this| {
+                this.auctions.remove(name);
+                if let Some(winner) = winner {
+                    this.move_name(name, auction.seller, winner)?;
+                    this.escrowed = this.escrowed.saturating_sub(auction.highest_bid);
+                    this.env()
+                        .transfer(auction.seller, auction.highest_bid)
+                        .map_err(|_| Error::TransferFailed)?;
+                } else if let Some(bidder) = auction.highest_bidder {
+                    this.credit_refund(bidder, auction.highest_bid);
+                }
+
+                this.env().emit_event(AuctionSettled {
+                    name,
+                    winner,
+                    amount: auction.highest_bid,
+                });
+
+                this.mark_mutated();
+                Ok(())
+            })
+        }
+
+        /// Returns the auction refunds the given account can withdraw.
+        #[ink(message)]
+        pub fn pending_withdrawal_of(&self, who: AccountId) -> Balance {
+            self.pending_withdrawals.get(who).unwrap_or_default()
+        }
+
+        /// Withdraw the auction refunds owed to the caller.
+        #[ink(message)]
+        pub fn withdraw_refund(&mut self) -> Result<()> {
+            let caller = self.env().caller();
+            let amount = self.pending_withdrawal_of(caller);
+            if amount == 0 {
+                return Err(Error::NothingToWithdraw);
+            }
+
+            self.non_reentrant(|this| {
+                this.pending_withdrawals.remove(caller);
+                this.escrowed = this.escrowed.saturating_sub(amount);
+                this.env()
+                    .transfer(caller, amount)
+                    .map_err(|_| Error::TransferFailed)?;
+
+                this.env().emit_event(Withdrawn { to: caller, amount });
+
+                this.mark_mutated();
+                Ok(())
+            })
+        }
+
+        /// Approve an operator to manage the records of an owned username.
+        ///
+        /// Operators cannot transfer or unregister the username, and are dropped whenever
+        /// its owner changes.
+        #[ink(message)]
+        pub fn set_operator(&mut self, name: Hash, operator: AccountId) -> Result<()> {
+            self.ensure_not_paused()?;
+            self.ensure_name_owner(name)?;
+            self.operators.insert(name, &operator);
+            self.mark_mutated();
+            Ok(())
+        }
+
+        /// Revoke the operator of an owned username.
+        #[ink(message)]
+        pub fn remove_operator(&mut self, name: Hash) -> Result<()> {
+            self.ensure_not_paused()?;
+            self.ensure_name_owner(name)?;
+            self.operators.remove(name);
+            self.mark_mutated();
+            Ok(())
+        }
+
+        /// Retrieves the operator of a username, if one was approved.
+        #[ink(message)]
+        pub fn operator_of(&self, name: Hash) -> Option<AccountId> {
+            self.operators.get(name)
+        }
+
+        /// Approve an account, such as a marketplace contract, to transfer an owned username.
+        ///
+        /// Only one spender can be approved per username. The approval is dropped whenever
+        /// the username changes hands.
+        #[ink(message)]
+        pub fn approve(&mut self, name: Hash, spender: AccountId) -> Result<()> {
+            self.ensure_not_paused()?;
+            self.ensure_name_owner(name)?;
+
+            self.approvals.insert(name, &spender);
+
+            self.mark_mutated();
+            Ok(())
+        }
+
+        /// Retrieves the account approved to transfer a username, if any.
+        #[ink(message)]
+        pub fn approved_of(&self, name: Hash) -> Option<AccountId> {
+            self.approvals.get(name)
+        }
+
+        /// Appoint a guardian that can recover an owned username if the owner loses its key.
+        ///
+        /// The guardian is dropped whenever the username changes hands, including through
+        /// a recovery, after which the new owner has to appoint one again.
+        #[ink(message)]
+        pub fn set_guardian(&mut self, name: Hash, guardian: AccountId) -> Result<()> {
+            self.ensure_not_paused()?;
+            self.ensure_name_owner(name)?;
+
+            self.guardians.insert(name, &guardian);
+
+            self.mark_mutated();
+            Ok(())
+        }
+
+        /// Retrieves the guardian of a username, if one was appointed.
+        #[ink(message)]
+        pub fn guardian_of(&self, name: Hash) -> Option<AccountId> {
+            self.guardians.get(name)
+        }
+
+        /// Recover a username to a new account as its guardian.
+        ///
+        /// Deliberately not subject to the minimum holding period, so that an owner who loses
+        /// its key right after registering can still get the name back.
+        #[ink(message)]
+        pub fn recover(&mut self, name: Hash, new_owner: AccountId) -> Result<()> {
+            self.ensure_not_paused()?;
+            let caller = self.env().caller();
+            let from = self.lookup_owner(name).ok_or(Error::UsernameNotFound)?;
+            if self.guardians.get(name) != Some(caller) {
+                return Err(Error::NotGuardian);
+            }
+            self.ensure_not_frozen(name)?;
+
+            self.move_name(name, from, new_owner)?;
+
+            self.env().emit_event(Recovered {
+                name,
+                guardian: caller,
+                new_owner,
+            });
+
+            Ok(())
+        }
+
+        /// Transfer a username from its owner to another account as its approved spender.
+        ///
+        /// The approval is used up even if `to` is the current owner. Like `transfer_name`,
+        /// transferring to the zero account unregisters the username.
+        #[ink(message)]
+        pub fn transfer_name_from(
+            &mut self,
+            name: Hash,
+            from: AccountId,
+            to: AccountId,
+        ) -> Result<()> {
+            self.ensure_not_paused()?;
+            let caller = self.env().caller();
+            match self.lookup_owner(name) {
+                None => return Err(Error::UsernameNotFound),
+                Some(owner) if owner != from => return Err(Error::CallerIsNotOwner),
+                Some(_) => {}
+            }
+            if self.approvals.get(name) != Some(caller) {
+                return Err(Error::NotAuthorized);
+            }
+            self.ensure_not_frozen(name)?;
+            self.ensure_not_in_grace(name)?;
+            self.ensure_min_hold(name)?;
+            if to == from {
+                self.approvals.remove(name);
+                self.mark_mutated();
+                return Ok(());
+            }
+            if to == AccountId::default() {
+                self.release_name(name, from);
+                self.env().emit_event(Unregister { name, from });
+                return Ok(());
+            }
+
+            self.move_name(name, from, to)?;
+
+            self.env().emit_event(NameTransferred { name, from, to });
+
+            Ok(())
+        }
+
+        /// Returns the nonce of a username.
+        ///
+        /// The nonce increases whenever the username is renamed, transferred, released or
+        /// has its records updated, and never goes back down. Signed payloads relayed on
+        /// behalf of an owner should bind to the contract account, the name hash, the
+        /// current nonce and the arguments of the intended call, so that they cannot be
+        /// replayed once the username changes.
+        #[ink(message)]
+        pub fn nonce_of(&self, name: Hash) -> u64 {
+            self.nonces.get(name).unwrap_or_default()
+        }
+
+        /// Returns the metadata version of a username.
+        ///
+        /// The version increases whenever the username is registered, renamed, transferred or
+        /// released, or has its address, address records, text records, content hash or
+        /// contract mark updated. Clients can cache records along with the version and refetch
+        /// them once it changes. Public keys belong to accounts rather than names and are tracked through
+        /// `PubkeyRotated` events instead.
+        #[ink(message)]
+        pub fn meta_version_of(&self, name: Hash) -> u64 {
+            self.meta_version.get(name).unwrap_or_default()
+        }
+
+        /// Renew a username, restarting its TTL from the current block.
+        ///
+        /// While the registration fee is zero only the owner can renew. Otherwise anyone can
+        /// renew any name by paying the fee, which leaves the owner unchanged. Names can still
+        /// be renewed during their grace period. Names past it cannot be renewed and have to
+        /// be registered again.
+        #[ink(message, payable)]
+        pub fn renew(&mut self, name: Hash) -> Result<()> {
+            self.ensure_not_paused()?;
+            let caller = self.env().caller();
+
+            let mut record = self.records.get(name).ok_or(Error::UsernameNotFound)?;
+            if self.registration_fee == 0 && record.owner != caller {
+                return Err(Error::CallerIsNotOwner);
+            }
+            if self.env().transferred_value() < self.registration_fee {
+                return Err(Error::InsufficientFee);
+            }
+            if self.record_status(&record) == NameStatus::Expired {
+                return Err(Error::NameExpired);
+            }
+
+            let now = self.env().block_timestamp();
+            record.registered_at = now;
+            self.records.insert(name, &record);
+            self.collect_fee();
+
+            self.env().emit_event(Renewed {
+                name,
+                renewer: caller,
+                new_expiry: now.saturating_add(self.ttl),
+            });
+
+            self.mark_mutated();
+            Ok(())
+        }
+
+        /// Publish the caller's public encryption key.
+        ///
+        /// Accounts do not need to own a username to publish a key.
+        #[ink(message)]
+        pub fn set_pubkey(&mut self, key: [u8; 32]) -> Result<()> {
+            self.ensure_not_paused()?;
+            let caller = self.env().caller();
+            let old_key = self.pubkeys.get(caller);
+            self.pubkeys.insert(caller, &key);
+
+            self.env().emit_event(PubkeyRotated {
+                who: caller,
+                old_key,
+                new_key: key,
+            });
+
+            self.mark_mutated();
+            Ok(())
+        }
+
+        /// Retrieves the public encryption key published by an account, if any.
+        #[ink(message)]
+        pub fn get_pubkey(&self, who: AccountId) -> Option<[u8; 32]> {
+            self.pubkeys.get(who)
+        }
+
+        /// Block an account from receiving the caller's public key through `resolve_full`.
+        ///
+        /// This only covers the handshake path of honest clients. Names still resolve, and
+        /// since contract storage is public, a blocked account can still read the key off
+        /// chain or through `get_pubkey`.
+        #[ink(message)]
+        pub fn block(&mut self, who: AccountId) -> Result<()> {
+            self.ensure_not_paused()?;
+            let caller = self.env().caller();
+            self.blocklist.insert((caller, who), &());
+            self.mark_mutated();
+            Ok(())
+        }
+
+        /// Unblock a previously blocked account.
+        #[ink(message)]
+        pub fn unblock(&mut self, who: AccountId) -> Result<()> {
+            self.ensure_not_paused()?;
+            let caller = self.env().caller();
+            self.blocklist.remove((caller, who));
+            self.mark_mutated();
+            Ok(())
+        }
+
+        /// Returns whether `blocker` has blocked `who`.
+        #[ink(message)]
+        pub fn is_blocked(&self, blocker: AccountId, who: AccountId) -> bool {
+            self.blocklist.contains((blocker, who))
+        }
+
+        /// Set the address record of a username for another chain.
+        ///
+        /// Callable by the owner or the operator of the username. The native coin type is
+        /// served by `set_address` instead. Usernames hold at most `MAX_ADDR_RECORDS` records,
+        /// which follow them when they get renamed and are dropped when they get released.
+        #[ink(message)]
+        pub fn set_addr(&mut self, name: Hash, coin_type: u32, addr: Vec<u8>) -> Result<()> {
+            self.ensure_not_paused()?;
+            self.ensure_name_manager(name)?;
+            if coin_type == NATIVE_COIN_TYPE {
+                return Err(Error::InvalidCoinType);
+            }
+            if addr.len() > MAX_ADDR_LEN {
+                return Err(Error::ValueTooLong);
+            }
+            let is_new = !self.addresses.contains((name, coin_type));
+            if is_new && self.coin_type_count.get(name).unwrap_or_default() >= MAX_ADDR_RECORDS {
+                return Err(Error::TooManyRecords);
+            }
+
+            if is_new {
+                self.add_coin_type(name, coin_type);
+            }
+            self.addresses.insert((name, coin_type), &addr);
+            self.bump_nonce(name);
+            self.bump_meta_version(name);
+
+            Ok(())
+        }
+
+        /// Retrieves the address record of a username for a coin type, if set.
+        ///
+        /// The native coin type returns the encoded `AccountId` the username resolves to.
+        /// Usernames past their grace period have no address records.
+        #[ink(message)]
+        pub fn get_addr(&self, name: Hash, coin_type: u32) -> Option<Vec<u8>> {
+            if coin_type == NATIVE_COIN_TYPE {
+                return self
+                    .lookup(name)
+                    .map(|account| AsRef::<[u8]>::as_ref(&account).to_vec());
+            }
+            self.lookup_owner(name)?;
+            self.addresses.get((name, coin_type))
+        }
+
+        /// Point an owned username at a profile page stored under a content identifier,
+        /// such as an IPFS or Arweave CID.
+        ///
+        /// The content hash follows the username when it gets renamed and is dropped when it
+        /// gets released or reclaimed.
+        #[ink(message)]
+        pub fn set_content_hash(&mut self, name: Hash, cid: Vec<u8>) -> Result<()> {
+            self.ensure_not_paused()?;
+            self.ensure_name_owner(name)?;
+            if cid.is_empty() {
+                return Err(Error::EmptyValue);
+            }
+            if cid.len() > MAX_CONTENT_HASH_LEN {
+                return Err(Error::ValueTooLong);
+            }
+
+            self.content_hash.insert(name, &cid);
+            self.bump_nonce(name);
+            self.bump_meta_version(name);
+
+            self.env().emit_event(ContentHashChanged { name, cid });
+
+            Ok(())
+        }
+
+        /// Retrieves the content hash of a username, if set.
+        #[ink(message)]
+        pub fn get_content_hash(&self, name: Hash) -> Option<Vec<u8>> {
+            self.content_hash.get(name)
+        }
+
+        /// Set a text record, such as a bio or website, on a username.
+        ///
+        /// Callable by the owner or the operator of the username. An empty value removes the
+        /// record. Usernames hold at most `MAX_TEXT_RECORDS` records, which follow them when
+        /// they get renamed and are dropped when they get released.
+        #[ink(message)]
+        pub fn set_text(&mut self, name: Hash, key: Vec<u8>, value: Vec<u8>) -> Result<()> {
+            self.ensure_not_paused()?;
+            self.ensure_name_manager(name)?;
+            self.ensure_text_len(&key)?;
+            self.ensure_text_len(&value)?;
+            let is_new = !self.text_records.contains((name, key.clone()));
+            if is_new
+                && !value.is_empty()
+                && self.text_key_count.get(name).unwrap_or_default() >= MAX_TEXT_RECORDS
+            {
+                return Err(Error::TooManyRecords);
+            }
+
+            if value.is_empty() {
+                self.remove_text_record(name, key);
+            } else {
+                if is_new {
+                    self.add_text_key(name, key.clone());
+                }
+                self.text_records.insert((name, key), &value);
+            }
+            self.bump_nonce(name);
+            self.bump_meta_version(name);
+
+            Ok(())
+        }
+
+        /// Retrieves a text record of a username, if set.
+        #[ink(message)]
+        pub fn get_text(&self, name: Hash, key: Vec<u8>) -> Option<Vec<u8>> {
+            self.text_records.get((name, key))
+        }
+
+        /// Delete a text record from a username.
+        ///
+        /// Callable by the owner or the operator of the username. Deleting a key that is not
+        /// set succeeds without changing anything.
+        #[ink(message)]
+        pub fn delete_text(&mut self, name: Hash, key: Vec<u8>) -> Result<()> {
+            self.ensure_not_paused()?;
+            self.ensure_name_manager(name)?;
+            if !self.text_records.contains((name, key.clone())) {
+                return Ok(());
+            }
+
+            self.remove_text_record(name, key.clone());
+            self.bump_nonce(name);
+            self.bump_meta_version(name);
+
+            self.env().emit_event(TextRecordDeleted { name, key });
+
+            Ok(())
+        }
+
+        /// Lists the keys of the text records set on a username.
+        ///
+        /// Keys are compacted on removal by moving the last key into the freed slot, so their
+        /// order can change over time.
+        #[ink(message)]
+        pub fn profile_keys(&self, name: Hash) -> Vec<Vec<u8>> {
+            (0..self.text_key_count.get(name).unwrap_or_default())
+                .filter_map(|i| self.text_keys.get((name, i)))
+                .collect()
+        }
+
+        /// Returns the maximum length of text record keys and values.
+        #[ink(message)]
+        pub fn max_text_len(&self) -> u32 {
+            self.max_text_len
+        }
+
+        /// Set the maximum length of text record keys and values.
+        #[ink(message)]
+        pub fn set_max_text_len(&mut self, max_text_len: u32) -> Result<()> {
+            self.ensure_owner()?;
+            self.max_text_len = max_text_len;
+            self.mark_mutated();
+            Ok(())
+        }
+
+        /// Returns the address `get_address` falls back to for unresolved names.
+        #[ink(message)]
+        pub fn default_address(&self) -> AccountId {
+            self.default_address
+        }
+
+        /// Set the address `get_address` falls back to for unresolved names.
+        #[ink(message)]
+        pub fn set_default_address(&mut self, who: AccountId) -> Result<()> {
+            self.ensure_owner()?;
+            let old_address = self.default_address;
+            self.default_address = who;
+
+            self.env().emit_event(DefaultAddressChanged {
+                old_address,
+                new_address: who,
+            });
+
+            self.mark_mutated();
+            Ok(())
+        }
+
+        /// Returns the fee required to register a username.
+        #[ink(message)]
+        pub fn fee(&self) -> Balance {
+            self.registration_fee
+        }
+
+        /// Set the fee required to register a username.
+        #[ink(message)]
+        pub fn set_fee(&mut self, fee: Balance) -> Result<()> {
+            self.ensure_owner()?;
+            self.registration_fee = fee;
+            self.mark_mutated();
+            Ok(())
+        }
+
+        /// Returns the registration price for a name of the given length.
+        ///
+        /// Lengths without a configured price fall back to the registration fee.
+        #[ink(message)]
+        pub fn price_for_len(&self, len: u8) -> Balance {
+            self.length_prices
+                .get(len.min(MAX_LENGTH_TIER))
+                .unwrap_or(self.registration_fee)
+        }
+
+        /// Returns the registration price of usernames registered by hash alone, without
+        /// their plaintext length.
+        ///
+        /// This is the highest price of any length tier, so short-name premiums cannot be
+        /// bypassed by hiding the length. It equals the registration fee while no tier is
+        /// priced above it. `register_with_len` and `register_plain` charge the exact tier.
+        #[ink(message)]
+        pub fn hash_only_price(&self) -> Balance {
+            (0..=MAX_LENGTH_TIER)
+                .map(|len| self.price_for_len(len))
+                .max()
+                .unwrap_or(self.registration_fee)
+        }
+
+        /// Set the registration price for a name length tier.
+        ///
+        /// Lengths of `MAX_LENGTH_TIER` and above share the price of that tier.
+        #[ink(message)]
+        pub fn set_length_price(&mut self, len: u8, price: Balance) -> Result<()> {
+            self.ensure_owner()?;
+            self.length_prices.insert(len.min(MAX_LENGTH_TIER), &price);
+            self.mark_mutated();
+            Ok(())
+        }
+
+        /// Returns the current balance of the contract.
+        #[ink(message)]
+        pub fn contract_balance(&self) -> Balance {
+            self.env().balance()
+        }
+
+        /// Returns the value paid for registrations over the lifetime of the contract.
+        ///
+        /// Withdrawals do not lower this total, compare with `contract_balance` for the
+        /// funds currently held.
+        #[ink(message)]
+        pub fn total_fees(&self) -> Balance {
+            self.total_fees_collected
+        }
+
+        /// Withdraw accumulated funds from the contract to its owner.
+        ///
+        /// Value escrowed for auction bidders cannot be withdrawn.
+        #[ink(message)]
+        pub fn withdraw(&mut self, amount: Balance) -> Result<()> {
+            self.ensure_owner()?;
+            if amount > self.env().balance().saturating_sub(self.escrowed) {
+                return Err(Error::InsufficientBalance);
+            }
+
+            self.non_reentrant(|this| {
+                this.env()
+                    .transfer(this.owner, amount)
+                    .map_err(|_| Error::TransferFailed)?;
+
+                this.env().emit_event(Withdrawn {
+                    to: this.owner,
+                    amount,
+                });
+
+                this.mark_mutated();
+                Ok(())
+            })
+        }
+
+        /// Forcibly release a username from its owner, e.g. after an abuse report.
+        ///
+        /// Drops all state tied to the registration, including its text records.
+        #[ink(message)]
+        pub fn admin_release(&mut self, name: Hash) -> Result<()> {
+            self.ensure_owner()?;
+            let former_owner = self.stored_owner(name).ok_or(Error::UsernameNotFound)?;
+
+            self.release_name(name, former_owner);
+
+            self.env().emit_event(AdminReleased { name, former_owner });
+
+            Ok(())
+        }
+
+        /// Freeze a username, blocking renames, transfers and releases by its owner.
+        ///
+        /// The username keeps resolving while frozen. The freeze is lifted when the username
+        /// gets released by the contract owner or reclaimed after expiry.
+        #[ink(message)]
+        pub fn freeze(&mut self, name: Hash) -> Result<()> {
+            self.ensure_owner()?;
+            self.frozen.insert(name, &());
+
+            self.env().emit_event(Frozen { name });
+
+            self.mark_mutated();
+            Ok(())
+        }
+
+        /// Unfreeze a username.
+        #[ink(message)]
+        pub fn unfreeze(&mut self, name: Hash) -> Result<()> {
+            self.ensure_owner()?;
+            self.frozen.remove(name);
+
+            self.env().emit_event(Unfrozen { name });
+
+            self.mark_mutated();
+            Ok(())
+        }
+
+        /// Returns whether a username is frozen.
+        #[ink(message)]
+        pub fn is_frozen(&self, name: Hash) -> bool {
+            self.frozen.contains(name)
+        }
+
+        /// Reserve a username so that it cannot be registered publicly.
+        #[ink(message)]
+        pub fn reserve(&mut self, name: Hash) -> Result<()> {
+            self.ensure_owner()?;
+            self.reserved.insert(name, &());
+            self.mark_mutated();
+            Ok(())
+        }
+
+        /// Reserve several usernames at once.
+        ///
+        /// Names that are already reserved are skipped, so the call can safely be repeated.
+        #[ink(message)]
+        pub fn reserve_many(&mut self, names: Vec<Hash>) -> Result<()> {
+            self.ensure_owner()?;
+            self.ensure_batch_size(names.len())?;
+
+            let mut count = 0;
+            for name in names {
+                if !self.reserved.contains(name) {
+                    self.reserved.insert(name, &());
+                    count += 1;
+                }
+            }
+
+            self.env().emit_event(BulkReserved { count });
+
+            self.mark_mutated();
+            Ok(())
+        }
+
+        /// Remove a username from the reserved set.
+        #[ink(message)]
+        pub fn unreserve(&mut self, name: Hash) -> Result<()> {
+            self.ensure_owner()?;
+            self.reserved.remove(name);
+            self.mark_mutated();
+            Ok(())
+        }
+
+        /// Returns whether a username is reserved.
+        #[ink(message)]
+        pub fn is_reserved(&self, name: Hash) -> bool {
+            self.reserved.contains(name)
+        }
+
+        /// Register a reserved username to the given account.
+        ///
+        /// The username stays reserved, so it cannot be registered publicly once released.
+        #[ink(message)]
+        pub fn assign_reserved(&mut self, name: Hash, to: AccountId) -> Result<()> {
+            self.ensure_owner()?;
+            self.register_to(name, to)?;
+            Ok(())
+        }
+
+        /// Approve an account to register usernames on behalf of others through `register_for`.
+        #[ink(message)]
+        pub fn add_registrar(&mut self, who: AccountId) -> Result<()> {
+            self.ensure_owner()?;
+            self.registrars.insert(who, &());
+
+            self.env().emit_event(RegistrarAdded { who });
+
+            self.mark_mutated();
+            Ok(())
+        }
+
+        /// Revoke the approval of a registrar.
+        #[ink(message)]
+        pub fn remove_registrar(&mut self, who: AccountId) -> Result<()> {
+            self.ensure_owner()?;
+            self.registrars.remove(who);
+
+            self.env().emit_event(RegistrarRemoved { who });
+
+            self.mark_mutated();
+            Ok(())
+        }
+
+        /// Returns whether an account is an approved registrar.
+        #[ink(message)]
+        pub fn is_registrar(&self, who: AccountId) -> bool {
+            self.registrars.contains(who)
+        }
+
+        /// Replace the code of the contract in place, keeping its storage and address.
+        ///
+        /// The new code must have been uploaded already and must keep the storage layout
+        /// compatible: existing fields of `UkeHumanDns` keep their order and types, and new
+        /// fields are only ever appended after the last one. Key and value types of existing
+        /// mappings cannot change either, and new error variants or events go at the end.
+        #[ink(message)]
+        pub fn set_code(&mut self, code_hash: Hash) -> Result<()> {
+            self.ensure_owner()?;
+            let mut hash = [0; 32];
+            hash.copy_from_slice(code_hash.as_ref());
+            ink_env::set_code_hash(&hash).map_err(|_| Error::UpgradeFailed)?;
+
+            self.env().emit_event(CodeUpgraded { code_hash });
+
+            self.mark_mutated();
+            Ok(())
+        }
+
+        /// Export a page of usernames along with their owners, for migration to a new deployment.
+        ///
+        /// Pages follow the name index, see `names_paged`.
+        #[ink(message)]
+        pub fn export_chunk(&self, start: u32, limit: u32) -> Result<Vec<(Hash, AccountId)>> {
+            self.ensure_owner()?;
+            Ok(self
+                .names_paged(start, limit)
+                .into_iter()
+                .filter_map(|name| self.stored_owner(name).map(|owner| (name, owner)))
+                .collect())
+        }
+
+        /// Export a page of usernames along with their owners in canonical order.
+        ///
+        /// Entries are sorted by the bytes of the name hash in ascending order, so the same
+        /// registry state always yields the same pages, e.g. to build a reproducible Merkle
+        /// tree over the SCALE encoded `(Hash, AccountId)` pairs. Expired names that have not
+        /// been reclaimed are included. Sorting reads the whole name index, so this is meant
+        /// for off-chain queries only. `limit` is capped at `MAX_PAGE_SIZE`.
+        #[ink(message)]
+        pub fn export_sorted(&self, start: u32, limit: u32) -> Vec<(Hash, AccountId)> {
+            let mut names: Vec<Hash> = (0..self.registration_count)
+                .filter_map(|i| self.names.get(i))
+                .collect();
+            names.sort_unstable_by(|a, b| a.as_ref().cmp(b.as_ref()));
+            names
+                .into_iter()
+                .skip(start as usize)
+                .take(limit.min(MAX_PAGE_SIZE) as usize)
+                .filter_map(|name| self.stored_owner(name).map(|owner| (name, owner)))
+                .collect()
+        }
+
+        /// Import usernames exported from a previous deployment.
+        ///
+        /// Only possible until the migration is finalized or the first public registration
+        /// happens. Either all entries get imported or none, and no existing name is
+        /// overwritten.
+        #[ink(message)]
+        pub fn import_chunk(&mut self, entries: Vec<(Hash, AccountId)>) -> Result<()> {
+            self.ensure_owner()?;
+            if !self.migration_open {
+                return Err(Error::MigrationClosed);
+            }
+            self.ensure_batch_size(entries.len())?;
+
+            for (i, (name, owner)) in entries.iter().enumerate() {
+                self.ensure_valid_hash(*name)?;
+                if self.is_taken(*name) || entries[..i].iter().any(|(other, _)| other == name) {
+                    return Err(Error::UsernameAlreadyExists);
+                }
+                let count = entries[..=i]
+                    .iter()
+                    .filter(|(_, other)| other == owner)
+                    .count();
+                self.ensure_can_hold(*owner, count as u32)?;
+            }
+            for (name, owner) in entries {
+                self.register_to(name, owner)?;
+            }
+
+            Ok(())
+        }
+
+        /// Close the migration, after which no more names can be imported.
+        #[ink(message)]
+        pub fn finalize_migration(&mut self) -> Result<()> {
+            self.ensure_owner()?;
+            self.migration_open = false;
+            self.mark_mutated();
+            Ok(())
+        }
+
+        /// Returns whether names can still be imported.
+        #[ink(message)]
+        pub fn is_migration_open(&self) -> bool {
+            self.migration_open
+        }
+
+        /// Returns the rate limit window length and the registrations allowed within it.
+        #[ink(message)]
+        pub fn rate_limit(&self) -> (Timestamp, u32) {
+            (self.rate_window, self.max_per_window)
+        }
+
+        /// Set the rate limit window length and the registrations allowed within it.
+        #[ink(message)]
+        pub fn set_rate_limit(&mut self, window: Timestamp, max_per_window: u32) -> Result<()> {
+            self.ensure_owner()?;
+            self.rate_window = window;
+            self.max_per_window = max_per_window;
+            self.mark_mutated();
+            Ok(())
+        }
+
+        /// Returns the maximum number of usernames a single account may hold.
+        #[ink(message)]
+        pub fn max_names_per_account(&self) -> u32 {
+            self.max_names_per_account
+        }
+
+        /// Set the maximum number of usernames a single account may hold.
+        ///
+        /// Accounts already above a lowered cap keep their names but cannot acquire new ones.
+        #[ink(message)]
+        pub fn set_max_names_per_account(&mut self, max: u32) -> Result<()> {
+            self.ensure_owner()?;
+            self.max_names_per_account = max;
+            self.mark_mutated();
+            Ok(())
+        }
+
+        /// Returns whether `Register` and `EditUsername` events are emitted.
+        #[ink(message)]
+        pub fn emit_events(&self) -> bool {
+            self.emit_events
+        }
+
+        /// Enable or disable the `Register` and `EditUsername` events.
+        ///
+        /// Turning them off saves gas for registrars minting names in bulk, but indexers of
+        /// such a deployment can no longer follow these changes through events and have to
+        /// rely on `export_chunk`, `names_of` and `meta_version_of` instead. Other events are
+        /// always emitted.
+        #[ink(message)]
+        pub fn set_emit_events(&mut self, enabled: bool) -> Result<()> {
+            self.ensure_owner()?;
+            self.emit_events = enabled;
+            self.mark_mutated();
+            Ok(())
+        }
+
+        /// Returns whether `StateChanged` events are emitted.
+        #[ink(message)]
+        pub fn state_changed_events(&self) -> bool {
+            self.state_changed_events
+        }
+
+        /// Enable or disable the `StateChanged` events, which are off by default.
+        ///
+        /// The specific events are emitted either way.
+        #[ink(message)]
+        pub fn set_state_changed_events(&mut self, enabled: bool) -> Result<()> {
+            self.ensure_owner()?;
+            self.state_changed_events = enabled;
+            self.mark_mutated();
+            Ok(())
+        }
+
+        /// Returns the maximum number of entries accepted by batch messages.
+        #[ink(message)]
+        pub fn max_batch(&self) -> u32 {
+            self.max_batch
+        }
+
+        /// Set the maximum number of entries accepted by batch messages.
+        #[ink(message)]
+        pub fn set_max_batch(&mut self, max_batch: u32) -> Result<()> {
+            self.ensure_owner()?;
+            self.max_batch = max_batch;
+            self.mark_mutated();
+            Ok(())
+        }
+
+        /// Assign the subname `label` under an owned username to an account.
+        ///
+        /// Subnames are tied to the current registration of the parent: once the parent
+        /// is released, renamed or reclaimed, its subnames stop resolving.
+        #[ink(message)]
+        pub fn create_subname(&mut self, parent: Hash, label: Hash, to: AccountId) -> Result<()> {
+            self.ensure_not_paused()?;
+            self.ensure_name_owner(parent)?;
+            self.ensure_not_frozen(parent)?;
+            self.ensure_valid_hash(label)?;
+            let parent_id = self
+                .registration_ids
+                .get(parent)
+                .ok_or(Error::UsernameNotFound)?;
+
+            self.subnames.insert((parent, label), &to);
+            self.subname_parents.insert((parent, label), &parent_id);
+
+            self.env().emit_event(SubnameCreated { parent, label, to });
+
+            self.mark_mutated();
+            Ok(())
+        }
+
+        /// Set the account resolving every subname of an owned username without an explicit
+        /// subname of its own.
+        ///
+        /// Like subnames, the wildcard stops resolving once the parent is released.
+        #[ink(message)]
+        pub fn set_wildcard(&mut self, parent: Hash, to: AccountId) -> Result<()> {
+            self.ensure_not_paused()?;
+            self.ensure_name_owner(parent)?;
+            self.ensure_not_frozen(parent)?;
+            let parent_id = self
+                .registration_ids
+                .get(parent)
+                .ok_or(Error::UsernameNotFound)?;
+
+            self.wildcard_target.insert(parent, &to);
+            self.wildcard_parents.insert(parent, &parent_id);
+
+            self.mark_mutated();
+            Ok(())
+        }
+
+        /// Resolves a subname under a username, if the parent registration still holds it.
+        ///
+        /// Falls back to the wildcard target of the parent if the label has no subname.
+        #[ink(message)]
+        pub fn resolve_subname(&self, parent: Hash, label: Hash) -> Option<AccountId> {
+            self.lookup_owner(parent)?;
+            let parent_id = self.registration_ids.get(parent)?;
+            if self.subname_parents.get((parent, label)) == Some(parent_id) {
+                return self.subnames.get((parent, label));
+            }
+            if self.wildcard_parents.get(parent) == Some(parent_id) {
+                return self.wildcard_target.get(parent);
+            }
+            None
+        }
+
+        /// Declare an owned username as the caller's primary name.
+        #[ink(message)]
+        pub fn set_primary(&mut self, name: Hash) -> Result<()> {
+            self.ensure_not_paused()?;
+            let caller = self.ensure_name_owner(name)?;
+
+            self.primary_name.insert(caller, &name);
+
+            self.mark_mutated();
+            Ok(())
+        }
+
+        /// Retrieves the primary username hash of an account.
+        ///
+        /// If no primary was declared, this falls back to the name with the lowest
+        /// registration id the account still holds, so releasing that name makes the next
+        /// oldest registration the fallback.
+        #[ink(message)]
+        pub fn primary_name_of(&self, who: AccountId) -> Option<Hash> {
+            self.primary_name.get(who).or_else(|| {
+                (0..self.owned_count.get(who).unwrap_or_default())
+                    .filter_map(|i| self.owned_names.get((who, i)))
+                    .min_by_key(|name| self.registration_ids.get(name).unwrap_or(u32::MAX))
+            })
+        }
+
+        /// Appends a newly registered username to the name index.
+        fn index_name(&mut self, name: Hash) {
+            let position = self.registration_count;
+            self.names.insert(position, &name);
+            self.name_positions.insert(name, &position);
+            self.registration_count += 1;
+        }
+
+        /// Removes a released username from the name index, moving the last entry into its slot.
+        fn unindex_name(&mut self, name: Hash) {
+            let last = self.registration_count - 1;
+            if let Some(position) = self.name_positions.get(name) {
+                if position != last {
+                    if let Some(moved) = self.names.get(last) {
+                        self.names.insert(position, &moved);
+                        self.name_positions.insert(moved, &position);
+                    }
+                }
+            }
+            self.names.remove(last);
+            self.name_positions.remove(name);
+            self.registration_count = last;
+        }
+
+        /// Appends a text record key to the list of keys set on a username.
+        fn add_text_key(&mut self, name: Hash, key: Vec<u8>) {
+            let position = self.text_key_count.get(name).unwrap_or_default();
+            self.text_keys.insert((name, position), &key);
+            self.text_key_positions.insert((name, key), &position);
+            self.text_key_count.insert(name, &(position + 1));
+        }
+
+        /// Removes a text record along with its key, moving the last key into its slot.
+        fn remove_text_record(&mut self, name: Hash, key: Vec<u8>) {
+            let position = match self.text_key_positions.get((name, key.clone())) {
+                Some(position) => position,
+                None => return,
+            };
+            let last = self.text_key_count.get(name).unwrap_or_default() - 1;
+            if position != last {
+                if let Some(moved) = self.text_keys.get((name, last)) {
+                    self.text_keys.insert((name, position), &moved);
+                    self.text_key_positions.insert((name, moved), &position);
+                }
+            }
+            self.text_keys.remove((name, last));
+            self.text_key_positions.remove((name, key.clone()));
+            self.text_key_count.insert(name, &last);
+            self.text_records.remove((name, key));
+        }
+
+        /// Removes all text records of a username.
+        fn clear_text_records(&mut self, name: Hash) {
+            for key in self.profile_keys(name) {
+                self.text_records.remove((name, key.clone()));
+                self.text_key_positions.remove((name, key));
+            }
+            for i in 0..self.text_key_count.get(name).unwrap_or_default() {
+                self.text_keys.remove((name, i));
+            }
+            self.text_key_count.remove(name);
+        }
+
+        /// Moves all text records of a renamed username to its new hash.
+        fn move_text_records(&mut self, old_name: Hash, new_name: Hash) {
+            for key in self.profile_keys(old_name) {
+                if let Some(value) = self.text_records.get((old_name, key.clone())) {
+                    self.add_text_key(new_name, key.clone());
+                    self.text_records.insert((new_name, key), &value);
+                }
+            }
+            self.clear_text_records(old_name);
+        }
+
+        /// Lists the coin types of the address records set on a username.
+        fn coin_types_of(&self, name: Hash) -> Vec<u32> {
+            (0..self.coin_type_count.get(name).unwrap_or_default())
+                .filter_map(|i| self.coin_types.get((name, i)))
+                .collect()
+        }
+
+        /// Appends a coin type to the list of address records set on a username.
+        fn add_coin_type(&mut self, name: Hash, coin_type: u32) {
+            let position = self.coin_type_count.get(name).unwrap_or_default();
+            self.coin_types.insert((name, position), &coin_type);
+            self.coin_type_count.insert(name, &(position + 1));
+        }
+
+        /// Removes all address records of a username.
+        fn clear_addr_records(&mut self, name: Hash) {
+            for coin_type in self.coin_types_of(name) {
+                self.addresses.remove((name, coin_type));
+            }
+            for i in 0..self.coin_type_count.get(name).unwrap_or_default() {
+                self.coin_types.remove((name, i));
+            }
+            self.coin_type_count.remove(name);
+        }
+
+        /// Moves all address records of a renamed username to its new hash.
+        fn move_addr_records(&mut self, old_name: Hash, new_name: Hash) {
+            for coin_type in self.coin_types_of(old_name) {
+                if let Some(addr) = self.addresses.get((old_name, coin_type)) {
+                    self.add_coin_type(new_name, coin_type);
+                    self.addresses.insert((new_name, coin_type), &addr);
+                }
+            }
+            self.clear_addr_records(old_name);
+        }
+
+        /// Replaces a renamed username in the name index, keeping its position.
+        fn reindex_name(&mut self, old_name: Hash, new_name: Hash) {
+            if let Some(position) = self.name_positions.get(old_name) {
+                self.name_positions.remove(old_name);
+                self.names.insert(position, &new_name);
+                self.name_positions.insert(new_name, &position);
+            }
+        }
+
+        /// Returns an error if `owner` cannot rename `old_name` into `new_name`.
+        fn ensure_can_rename(
+            &self,
+            old_name: Hash,
+            new_name: Hash,
+            owner: AccountId,
+        ) -> Result<()> {
+            match self.lookup_owner(old_name) {
+                None => return Err(Error::UsernameNotFound),
+                Some(current) if current != owner => return Err(Error::CallerIsNotOwner),
+                Some(_) => {}
+            }
+            self.ensure_not_frozen(old_name)?;
+            self.ensure_not_in_grace(old_name)?;
+            if old_name == new_name {
+                return Err(Error::NoChange);
+            }
+            self.ensure_valid_hash(new_name)?;
+            self.ensure_not_reserved(new_name)?;
+            if self.is_taken(new_name) {
+                return Err(Error::UsernameAlreadyExists);
+            }
+            Ok(())
+        }
+
+        /// Swaps the hash of a username for a new one, carrying over its registration state.
+        fn rename(&mut self, old_name: Hash, new_name: Hash, owner: AccountId) {
+            if let Some(previous_owner) = self.stored_owner(new_name) {
+                self.release_name(new_name, previous_owner);
+            }
+            let target = self.username_to_id.get(old_name).unwrap_or(owner);
+            let record = self.records.get(old_name).unwrap_or(Record {
+                owner,
+                registered_at: 0,
+                created_at: 0,
+                flags: 0,
+                algo: ALGO_BLAKE2X256,
+            });
+            self.username_to_id.remove(old_name);
+            self.records.remove(old_name);
+            self.clear_delegations(old_name);
+            self.username_to_id.insert(new_name, &target);
+            self.records.insert(new_name, &Record { owner, ..record });
+            if let Some(id) = self.registration_ids.get(old_name) {
+                self.registration_ids.remove(old_name);
+                self.registration_ids.insert(new_name, &id);
+            }
+            if let Some(lease) = self.leases.get(old_name) {
+                self.leases.remove(old_name);
+                self.leases.insert(new_name, &lease);
+            }
+            if let Some(alias) = self.aliases.get(old_name) {
+                self.aliases.remove(old_name);
+                if self.alias_leads_to(alias, new_name) {
+                    self.set_record_flag(new_name, FLAG_ALIASED, false);
+                } else {
+                    self.aliases.insert(new_name, &alias);
+                }
+            }
+            if let Some(cid) = self.content_hash.get(old_name) {
+                self.content_hash.remove(old_name);
+                self.content_hash.insert(new_name, &cid);
+            }
+            self.move_text_records(old_name, new_name);
+            self.move_addr_records(old_name, new_name);
+            self.reindex_name(old_name, new_name);
+            self.rename_owned(owner, old_name, new_name);
+            self.bump_nonce(old_name);
+            self.bump_meta_version(old_name);
+            self.bump_nonce(new_name);
+            self.bump_meta_version(new_name);
+            if self.primary_name.get(owner) == Some(old_name) {
+                self.primary_name.insert(owner, &new_name);
+            }
+
+            if self.emit_events {
+                self.env().emit_event(EditUsername {
+                    old_name,
+                    new_name,
+                    from: owner,
+                });
+            }
+            self.record_owner(old_name, None);
+            self.record_owner(new_name, Some(owner));
+            self.emit_state_changed(new_name, KIND_EDIT);
+        }
+
+        /// Removes a username and all state tied to its registration.
+        fn release_name(&mut self, name: Hash, owner: AccountId) {
+            self.username_to_id.remove(name);
+            self.records.remove(name);
+            self.leases.remove(name);
+            self.aliases.remove(name);
+            self.registration_ids.remove(name);
+            self.content_hash.remove(name);
+            self.clear_text_records(name);
+            self.clear_addr_records(name);
+            self.frozen.remove(name);
+            self.bump_nonce(name);
+            self.bump_meta_version(name);
+            self.mix_state_root(name, false);
+            self.clear_delegations(name);
+            self.clear_account_pointers(owner, name);
+            self.unindex_name(name);
+            self.record_owner(name, None);
+            self.emit_state_changed(name, KIND_RELEASE);
+        }
+
+        /// Emits a `StateChanged` event if they are enabled.
+        fn emit_state_changed(&self, name: Hash, kind: u8) {
+            if self.state_changed_events {
+                self.env().emit_event(StateChanged { name, kind });
+            }
+        }
+
+        /// Mixes a registration or release of a username into the rolling state hash.
+        fn mix_state_root(&mut self, name: Hash, registered: bool) {
+            self.state_root = Hash::from(self.env().hash_encoded::<Blake2x256, _>(&(
+                self.state_root,
+                name,
+                registered,
+            )));
+        }
+
+        /// Increments the metadata version of a username after a change to its records or owner.
+        fn bump_meta_version(&mut self, name: Hash) {
+            let version = self.meta_version.get(name).unwrap_or_default();
+            self.meta_version.insert(name, &(version + 1));
+            self.mark_mutated();
+        }
+
+        /// Records the current block as the block of the most recent state change.
+        fn mark_mutated(&mut self) {
+            self.last_mutation_block = self.env().block_number();
+        }
+
+        /// Appends an ownership change of a username to its history, `None` for a release.
+        fn record_owner(&mut self, name: Hash, owner: Option<AccountId>) {
+            let len = self.owner_history_len.get(name).unwrap_or_default();
+            self.owner_history.insert(
+                (name, len % OWNER_HISTORY_DEPTH),
+                &(self.env().block_number(), owner),
+            );
+            self.owner_history_len.insert(name, &len.wrapping_add(1));
+        }
+
+        /// Increments the nonce of a username after a change to its state.
+        fn bump_nonce(&mut self, name: Hash) {
+            let nonce = self.nonces.get(name).unwrap_or_default();
+            self.nonces.insert(name, &(nonce + 1));
+        }
+
+        /// Appends a username to the list of names held by an account.
+        fn add_owned(&mut self, who: AccountId, name: Hash) {
+            let position = self.owned_count.get(who).unwrap_or_default();
+            self.owned_names.insert((who, position), &name);
+            self.owned_positions.insert(name, &position);
+            self.owned_count.insert(who, &(position + 1));
+        }
+
+        /// Removes a username from the list of names held by an account, moving the last
+        /// name into its slot.
+        fn remove_owned(&mut self, who: AccountId, name: Hash) {
+            let position = match self.owned_positions.get(name) {
+                Some(position) if self.owned_names.get((who, position)) == Some(name) => position,
+                _ => return,
+            };
+            let last = self.owned_count.get(who).unwrap_or_default() - 1;
+            if position != last {
+                if let Some(moved) = self.owned_names.get((who, last)) {
+                    self.owned_names.insert((who, position), &moved);
+                    self.owned_positions.insert(moved, &position);
+                }
+            }
+            self.owned_names.remove((who, last));
+            self.owned_positions.remove(name);
+            self.owned_count.insert(who, &last);
+        }
+
+        /// Replaces a renamed username in the list of names held by an account.
+        fn rename_owned(&mut self, who: AccountId, old_name: Hash, new_name: Hash) {
+            if let Some(position) = self.owned_positions.get(old_name) {
+                self.owned_positions.remove(old_name);
+                self.owned_names.insert((who, position), &new_name);
+                self.owned_positions.insert(new_name, &position);
+            }
+        }
+
+        /// Drops the listing, auction, pending offer, operator, approval and guardian of a
+        /// username whose owner changes.
+        ///
+        /// The highest bid of a dropped auction is credited back to its bidder.
+        fn clear_delegations(&mut self, name: Hash) {
+            self.listings.remove(name);
+            if let Some(auction) = self.auctions.get(name) {
+                self.auctions.remove(name);
+                if let Some(bidder) = auction.highest_bidder {
+                    self.credit_refund(bidder, auction.highest_bid);
+                }
+            }
+            self.offers.remove(name);
+            self.operators.remove(name);
+            self.approvals.remove(name);
+            self.guardians.remove(name);
+        }
+
+        /// Moves a username from one account to another, dropping its delegations.
+        ///
+        /// The username resolves to its new owner afterwards. Fails without changing anything
+        /// if the new owner is the zero account, which no one controls, or already holds the
+        /// maximum number of usernames.
+        fn move_name(&mut self, name: Hash, from: AccountId, to: AccountId) -> Result<()> {
+            if to == AccountId::default() {
+                return Err(Error::InvalidRecipient);
+            }
+            self.ensure_can_hold(to, 1)?;
+            self.username_to_id.insert(name, &to);
+            if let Some(record) = self.records.get(name) {
+                self.records.insert(
+                    name,
+                    &Record {
+                        owner: to,
+                        flags: 0,
+                        ..record
+                    },
+                );
+            }
+            self.leases.remove(name);
+            self.aliases.remove(name);
+            self.bump_nonce(name);
+            self.bump_meta_version(name);
+            self.clear_delegations(name);
+            self.clear_account_pointers(from, name);
+            self.add_owned(to, name);
+            self.record_owner(name, Some(to));
+            self.emit_state_changed(name, KIND_TRANSFER);
+            Ok(())
+        }
+
+        /// Removes the index entry and the primary pointer of an account to a name it no
+        /// longer holds.
+        fn clear_account_pointers(&mut self, who: AccountId, name: Hash) {
+            self.remove_owned(who, name);
+            if self.primary_name.get(who) == Some(name) {
+                self.primary_name.remove(who);
+            }
+        }
+
+        /// Returns the caller if it owns the given username, or an error otherwise.
+        fn ensure_name_owner(&self, name: Hash) -> Result<AccountId> {
+            let caller = self.env().caller();
+            if !self.username_to_id.contains(name) {
+                return Err(Error::UsernameNotFound);
+            }
+            if self.lookup_owner(name) != Some(caller) {
+                return Err(Error::CallerIsNotOwner);
+            }
+            Ok(caller)
+        }
+
+        /// Returns the caller if it owns or operates the given username, or an error otherwise.
+        fn ensure_name_manager(&self, name: Hash) -> Result<AccountId> {
+            let caller = self.env().caller();
+            if !self.username_to_id.contains(name) {
+                return Err(Error::UsernameNotFound);
+            }
+            if self.lookup_owner(name) != Some(caller) && self.operators.get(name) != Some(caller) {
+                return Err(Error::NotAuthorized);
+            }
+            Ok(caller)
+        }
+
+        /// Returns an error if the caller is not the contract owner.
+        fn ensure_owner(&self) -> Result<()> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotAdmin);
+            }
+            Ok(())
+        }
+
+        /// Runs `f` with the reentrancy lock held, failing if it is already held.
+        ///
+        /// Every message that transfers value out of the contract goes through this, and
+        /// updates its state before transferring.
+        fn non_reentrant<T>(&mut self, f: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+            if self.locked.contains(()) {
+                return Err(Error::Reentrancy);
+            }
+            self.locked.insert((), &());
+            let result = f(self);
+            self.locked.remove(());
+            result
+        }
+
+        /// Credits an auction refund to an account, to be pulled through `withdraw_refund`.
+        fn credit_refund(&mut self, to: AccountId, amount: Balance) {
+            let pending = self.pending_withdrawal_of(to);
+            self.pending_withdrawals
+                .insert(to, &pending.saturating_add(amount));
+        }
+
+        /// Returns an error if the caller is neither the contract owner nor a registrar.
+        fn ensure_registrar(&self) -> Result<()> {
+            let caller = self.env().caller();
+            if caller != self.owner && !self.registrars.contains(caller) {
+                return Err(Error::NotAuthorized);
+            }
+            Ok(())
+        }
+
+        /// Returns an error if the contract is paused.
+        fn ensure_not_paused(&self) -> Result<()> {
+            if self.paused {
+                return Err(Error::ContractPaused);
+            }
+            Ok(())
+        }
+
+        /// Lowercases the ASCII letters of a plaintext username.
+        fn normalize(name: &[u8]) -> Vec<u8> {
+            name.to_ascii_lowercase()
+        }
+
+        /// Checks the length and characters of a plaintext username.
+        fn validate_name(&self, name: &[u8]) -> Result<()> {
+            if name.len() < MIN_NAME_LEN {
+                return Err(Error::NameTooShort);
+            }
+            if name.len() > MAX_NAME_LEN {
+                return Err(Error::NameTooLong);
+            }
+            if !name.iter().all(|c| self.charset_policy.allows(*c)) {
+                return Err(Error::InvalidCharacter);
+            }
+            Ok(())
+        }
+
+        /// Returns an error if the username is in its grace period.
+        fn ensure_not_in_grace(&self, name: Hash) -> Result<()> {
+            if self.name_status(name) == NameStatus::Grace {
+                return Err(Error::InGracePeriod);
+            }
+            Ok(())
+        }
+
+        /// Returns an error if the username was registered less than `min_hold` ago, not
+        /// counting renewals.
+        fn ensure_min_hold(&self, name: Hash) -> Result<()> {
+            let created_at = self
+                .records
+                .get(name)
+                .map(|record| record.created_at)
+                .unwrap_or_default();
+            if self.env().block_timestamp().saturating_sub(created_at) < self.min_hold {
+                return Err(Error::MinHoldNotMet);
+            }
+            Ok(())
+        }
+
+        /// Returns an error if the username is frozen.
+        fn ensure_not_frozen(&self, name: Hash) -> Result<()> {
+            if self.frozen.contains(name) {
+                return Err(Error::NameFrozen);
+            }
+            Ok(())
+        }
+
+        /// Hashes the fields an account signs to have a username registered on its behalf.
+        fn signed_registration_message(&self, name: Hash, owner: AccountId) -> [u8; 32] {
+            self.env().hash_encoded::<Blake2x256, _>(&(
+                self.env().account_id(),
+                name,
+                owner,
+                self.signing_nonce_of(owner),
+            ))
+        }
+
+        /// Returns an error if the username hash is the default hash.
+        fn ensure_valid_hash(&self, name: Hash) -> Result<()> {
+            if name == Hash::default() {
+                return Err(Error::InvalidName);
+            }
+            Ok(())
+        }
+
+        /// Returns an error if public registrations have to go through commit-reveal.
+        fn ensure_commit_reveal_off(&self) -> Result<()> {
+            if self.min_commitment_age > 0 {
+                return Err(Error::CommitmentRequired);
+            }
+            Ok(())
+        }
+
+        /// Returns an error if the username is reserved.
+        fn ensure_not_reserved(&self, name: Hash) -> Result<()> {
+            if self.reserved.contains(name) {
+                return Err(Error::NameReserved);
+            }
+            Ok(())
+        }
+
+        /// Returns an error if the transferred value does not cover `count` registrations by
+        /// hash alone.
+        fn ensure_fee_paid(&self, count: u32) -> Result<()> {
+            let fee = self.hash_only_price().saturating_mul(count.into());
+            if self.env().transferred_value() < fee {
+                return Err(Error::InsufficientFee);
+            }
+            Ok(())
+        }
+
+        /// Adds the value transferred with a successful registration to the collected fees.
+        fn collect_fee(&mut self) {
+            self.total_fees_collected = self
+                .total_fees_collected
+                .saturating_add(self.env().transferred_value());
+        }
+
+        /// Returns an error if `who` cannot take on `count` more usernames.
+        fn ensure_can_hold(&self, who: AccountId, count: u32) -> Result<()> {
+            let held = self.owned_count.get(who).unwrap_or_default();
+            if held.saturating_add(count) > self.max_names_per_account {
+                return Err(Error::TooManyNames);
+            }
+            Ok(())
+        }
+
+        /// Returns an error if a batch holds more than `max_batch` entries.
+        fn ensure_batch_size(&self, len: usize) -> Result<()> {
+            if len > self.max_batch as usize {
+                return Err(Error::BatchTooLarge);
+            }
+            Ok(())
+        }
+
+        /// Returns an error if a record key or value exceeds `max_text_len` bytes.
+        fn ensure_text_len(&self, bytes: &[u8]) -> Result<()> {
+            if bytes.len() > self.max_text_len as usize {
+                return Err(Error::ValueTooLong);
+            }
+            Ok(())
+        }
+
+        /// Returns whether a username is registered and cannot be reclaimed yet.
+        fn is_taken(&self, name: Hash) -> bool {
+            !matches!(
+                self.name_status(name),
+                NameStatus::Unregistered | NameStatus::Expired
+            )
+        }
+
+        /// Returns the rate limit window of an account after registering `count` more names.
+        fn check_rate_limit(&self, who: AccountId, count: u32) -> Result<(Timestamp, u32)> {
+            let now = self.env().block_timestamp();
+            let (start, registered) = match self.rate_limits.get(who) {
+                Some((start, registered)) if now.saturating_sub(start) <= self.rate_window => {
+                    (start, registered)
+                }
+                _ => (now, 0),
+            };
+            let registered = registered.saturating_add(count);
+            if registered > self.max_per_window {
+                return Err(Error::RateLimited);
+            }
+            Ok((start, registered))
+        }
+
+        /// Registers a username on behalf of a public caller, applying reservations and rate limits.
+        fn register_public(&mut self, name: Hash, owner: AccountId) -> Result<u32> {
+            self.ensure_not_reserved(name)?;
+            let window = self.check_rate_limit(owner, 1)?;
+            let id = self.register_to(name, owner)?;
+            self.rate_limits.insert(owner, &window);
+            self.migration_open = false;
+            self.collect_fee();
+            Ok(id)
+        }
+
+        /// Registers a username to `owner`, reclaiming it if the previous registration expired.
+        ///
+        /// Returns the registration id assigned to the username.
+        fn register_to(&mut self, name: Hash, owner: AccountId) -> Result<u32> {
+            self.ensure_valid_hash(name)?;
+            if self.is_taken(name) {
+                return Err(Error::UsernameAlreadyExists);
+            }
+            self.ensure_can_hold(owner, 1)?;
+            let previous_owner = self.stored_owner(name);
+            if let Some(previous_owner) = previous_owner {
+                self.clear_delegations(name);
+                self.clear_account_pointers(previous_owner, name);
+                self.leases.remove(name);
+                self.aliases.remove(name);
+                self.content_hash.remove(name);
+                self.clear_text_records(name);
+                self.clear_addr_records(name);
+                self.frozen.remove(name);
+            } else {
+                self.index_name(name);
+            }
+            let timestamp = self.env().block_timestamp();
+            self.username_to_id.insert(name, &owner);
+            self.records.insert(
+                name,
+                &Record {
+                    owner,
+                    registered_at: timestamp,
+                    created_at: timestamp,
+                    flags: 0,
+                    algo: ALGO_BLAKE2X256,
+                },
+            );
+            self.add_owned(owner, name);
+            let id = self.next_registration_id;
+            self.registration_ids.insert(name, &id);
+            self.next_registration_id += 1;
+            self.mix_state_root(name, true);
+            self.bump_meta_version(name);
+
+            if self.emit_events {
+                self.env().emit_event(Register {
+                    name,
+                    from: owner,
+                    id,
+                    timestamp,
+                });
+            }
+            self.record_owner(name, Some(owner));
+            self.emit_state_changed(name, KIND_REGISTER);
+            if let Some(previous_owner) = previous_owner {
+                self.env().emit_event(NameReclaimed {
+                    name,
+                    previous_owner,
+                    new_owner: owner,
+                });
+            }
+
+            Ok(id)
+        }
+
+        /// Returns whether the alias chain starting at `start` reaches `name`, counting chains
+        /// longer than `MAX_ALIAS_WALK` hops as reaching it.
+        fn alias_leads_to(&self, start: Hash, name: Hash) -> bool {
+            let mut current = start;
+            for _ in 0..MAX_ALIAS_WALK {
+                if current == name {
+                    return true;
+                }
+                match self.aliases.get(current) {
+                    Some(next) => current = next,
+                    None => return false,
+                }
+            }
+            true
+        }
+
+        /// Returns the address given the hash, treating names past their grace period as absent.
+        fn lookup(&self, name: Hash) -> Option<AccountId> {
+            self.lookup_at(name, 0)
+        }
+
+        /// Resolves a username reached after following `hops` aliases.
+        fn lookup_at(&self, name: Hash, hops: u32) -> Option<AccountId> {
+            let record = self.records.get(name)?;
+            if self.record_status(&record) == NameStatus::Expired {
+                return None;
+            }
+            if record.flags & FLAG_LEASED != 0 {
+                if let Some((lessee, _)) = self.lease_of(name) {
+                    return Some(lessee);
+                }
+            }
+            if record.flags & FLAG_ALIASED != 0 {
+                let target_name = self.aliases.get(name)?;
+                if hops == MAX_ALIAS_DEPTH || self.stored_owner(target_name) != Some(record.owner) {
+                    return None;
+                }
+                return self.lookup_at(target_name, hops + 1);
+            }
+            self.username_to_id.get(name)
+        }
+
+        /// Returns the owner of a username, treating names past their grace period as absent.
+        fn lookup_owner(&self, name: Hash) -> Option<AccountId> {
+            let record = self.records.get(name)?;
+            if self.record_status(&record) == NameStatus::Expired {
+                return None;
+            }
+            Some(record.owner)
+        }
+
+        /// Returns the stored owner of a username, including names past their grace period.
+        fn stored_owner(&self, name: Hash) -> Option<AccountId> {
+            self.records.get(name).map(|record| record.owner)
+        }
+
+        /// Sets or clears a flag on the record of a registered username.
+        fn set_record_flag(&mut self, name: Hash, flag: u8, enabled: bool) {
+            if let Some(mut record) = self.records.get(name) {
+                if enabled {
+                    record.flags |= flag;
+                } else {
+                    record.flags &= !flag;
+                }
+                self.records.insert(name, &record);
+            }
+        }
+
+        /// Returns the address given the hash or the default address.
+        fn get_address_or_default(&self, name: Hash) -> AccountId {
+            self.lookup(name).unwrap_or(self.default_address)
+        }
+    }
+
+    impl Resolver for UkeHumanDns {
+        /// Resolves a username to its account, or `None` if it is unregistered or expired.
+        #[ink(message)]
+        fn resolve(&self, name: Hash) -> Option<AccountId> {
+            self.lookup(name)
+        }
+
+        /// Retrieves the username hash registered to an account, if any.
+        ///
+        /// This is the account's primary name, as returned by `primary_name_of`.
+        #[ink(message)]
+        fn get_name(&self, who: AccountId) -> Option<Hash> {
+            self.primary_name_of(who)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+
+        use super::*;
+        use ink_lang as ink;
+
+        type Event = <UkeHumanDns as ::ink_lang::reflect::ContractEventBase>::Type;
+
+        fn set_next_caller(caller: AccountId) {
+            ink_env::test::set_caller::<Environment>(caller);
+        }
+
+        fn default_accounts() -> ink_env::test::DefaultAccounts<ink_env::DefaultEnvironment> {
+            ink_env::test::default_accounts::<Environment>()
+        }
+
+        fn hash_of(name: &[u8]) -> Hash {
+            let mut output = [0u8; 32];
+            ink_env::hash_bytes::<Blake2x256>(name, &mut output);
+            Hash::from(output)
+        }
+
+        fn contract_id() -> AccountId {
+            ink_env::test::callee::<Environment>()
+        }
+
+        fn balance_of(account: AccountId) -> Balance {
+            ink_env::test::get_account_balance::<Environment>(account).unwrap()
+        }
+
+        #[ink::test]
+        fn default_works() {
+            let uke_human_dns = UkeHumanDns::new();
+            assert_eq!(uke_human_dns.default_address, Default::default());
+        }
+
+        #[ink::test]
+        fn owner_works() {
+            let default_accounts = default_accounts();
+
+            set_next_caller(default_accounts.alice);
+            let contract = UkeHumanDns::new();
+            assert_eq!(contract.owner(), default_accounts.alice);
+            assert_eq!(contract.ensure_owner(), Ok(()));
+
+            set_next_caller(default_accounts.bob);
+            assert_eq!(contract.ensure_owner(), Err(Error::NotAdmin));
+        }
+
+        #[ink::test]
+        fn ownership_transfer_works() {
+            let default_accounts = default_accounts();
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            assert_eq!(contract.transfer_ownership(default_accounts.bob), Ok(()));
+            assert_eq!(contract.pending_owner(), Some(default_accounts.bob));
+            assert_eq!(contract.owner(), default_accounts.alice);
+
+            set_next_caller(default_accounts.charlie);
+            assert_eq!(contract.accept_ownership(), Err(Error::NotPendingOwner));
+            assert_eq!(
+                contract.transfer_ownership(default_accounts.charlie),
+                Err(Error::NotAdmin)
+            );
+
+            set_next_caller(default_accounts.bob);
+            assert_eq!(contract.accept_ownership(), Ok(()));
+            assert_eq!(contract.owner(), default_accounts.bob);
+            assert_eq!(contract.pending_owner(), None);
+        }
+
+        #[ink::test]
+        fn pause_works() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+            let new_name = Hash::from([0x02; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            contract.register(name).unwrap();
+
+            set_next_caller(default_accounts.bob);
+            assert_eq!(contract.pause(), Err(Error::NotAdmin));
+
+            set_next_caller(default_accounts.alice);
+            assert_eq!(contract.pause(), Ok(()));
+            assert!(contract.is_paused());
+            assert_eq!(contract.register(new_name), Err(Error::ContractPaused));
+            assert_eq!(
+                contract.edit_username(name, new_name),
+                Err(Error::ContractPaused)
+            );
+            assert_eq!(contract.get_address(name), default_accounts.alice);
+
+            assert_eq!(contract.unpause(), Ok(()));
+            assert_eq!(contract.edit_username(name, new_name), Ok(()));
+        }
+
+        #[ink::test]
+        fn register_works() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+
+            assert_eq!(contract.register(name), Ok(0));
+            assert_eq!(contract.register(name), Err(Error::UsernameAlreadyExists));
+        }
+
+        #[ink::test]
+        fn register_plain_works() {
+            let default_accounts = default_accounts();
+            let name = hash_of(b"alice");
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            assert_eq!(
+                contract.register_plain(b"al".to_vec()),
+                Err(Error::NameTooShort)
+            );
+            assert_eq!(
+                contract.register_plain([b'a'; 33].to_vec()),
+                Err(Error::NameTooLong)
+            );
+            assert_eq!(
+                contract.register_plain(b"al ice".to_vec()),
+                Err(Error::InvalidCharacter)
+            );
+
+            assert_eq!(contract.register_plain(b"alice".to_vec()), Ok(()));
+            assert_eq!(contract.resolve(name), Some(default_accounts.alice));
+            assert_eq!(
+                contract.register_plain(b"alice".to_vec()),
+                Err(Error::UsernameAlreadyExists)
+            );
+        }
+
+        #[ink::test]
+        fn register_with_algo_works() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+            let other = Hash::from([0x02; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            assert_eq!(
+                contract.register_with_algo(name, 3),
+                Err(Error::UnsupportedAlgo)
+            );
+            assert_eq!(contract.algo_of(name), None);
+            assert_eq!(contract.register_with_algo(name, ALGO_KECCAK256), Ok(0));
+            assert_eq!(contract.algo_of(name), Some(ALGO_KECCAK256));
+            assert_eq!(contract.resolve(name), Some(default_accounts.alice));
+
+            contract.register(other).unwrap();
+            assert_eq!(contract.algo_of(other), Some(ALGO_BLAKE2X256));
+            contract.transfer_name(name, default_accounts.bob).unwrap();
+            assert_eq!(contract.algo_of(name), Some(ALGO_KECCAK256));
+        }
+
+        #[ink::test]
+        fn charset_policy_works() {
+            let default_accounts = default_accounts();
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            assert_eq!(contract.charset_policy(), CharsetPolicy::AlphaNumDash);
+            assert_eq!(contract.register_plain(b"al-1".to_vec()), Ok(()));
+
+            assert_eq!(contract.set_charset_policy(CharsetPolicy::AlphaNum), Ok(()));
+            assert_eq!(
+                contract.register_plain(b"bo-b".to_vec()),
+                Err(Error::InvalidCharacter)
+            );
+            assert_eq!(contract.register_plain(b"bob1".to_vec()), Ok(()));
+
+            assert_eq!(
+                contract.set_charset_policy(CharsetPolicy::AsciiLower),
+                Ok(())
+            );
+            assert_eq!(
+                contract.register_plain(b"carl2".to_vec()),
+                Err(Error::InvalidCharacter)
+            );
+            assert_eq!(contract.register_plain(b"Carl".to_vec()), Ok(()));
+            assert_eq!(
+                contract.resolve(hash_of(b"al-1")),
+                Some(default_accounts.alice)
+            );
+
+            set_next_caller(default_accounts.bob);
+            assert_eq!(
+                contract.set_charset_policy(CharsetPolicy::AlphaNum),
+                Err(Error::NotAdmin)
+            );
+        }
+
+        #[ink::test]
+        fn register_plain_normalizes_case() {
+            let default_accounts = default_accounts();
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            assert_eq!(contract.name_hash(b"Alice".to_vec()), hash_of(b"alice"));
+            assert_eq!(contract.register_plain(b"Alice".to_vec()), Ok(()));
+
+            set_next_caller(default_accounts.bob);
+            assert_eq!(
+                contract.register_plain(b"aLiCe".to_vec()),
+                Err(Error::UsernameAlreadyExists)
+            );
+            assert_eq!(
+                contract.register_plain("alicé".as_bytes().to_vec()),
+                Err(Error::InvalidCharacter)
+            );
+        }
+
+        #[ink::test]
+        fn register_many_works() {
+            let default_accounts = default_accounts();
+            let first = Hash::from([0x01; 32]);
+            let second = Hash::from([0x02; 32]);
+            let third = Hash::from([0x03; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            contract.register(third).unwrap();
+
+            assert_eq!(
+                contract.register_many(vec![first, second, third]),
+                Err(Error::UsernameAlreadyExists)
+            );
+            assert_eq!(
+                contract.register_many(vec![first, first]),
+                Err(Error::UsernameAlreadyExists)
+            );
+            assert_eq!(contract.resolve(first), None);
+
+            contract.set_max_batch(1).unwrap();
+            assert_eq!(
+                contract.register_many(vec![first, second]),
+                Err(Error::BatchTooLarge)
+            );
+
+            contract.set_max_batch(2).unwrap();
+            assert_eq!(contract.register_many(vec![first, second]), Ok(()));
+            assert_eq!(contract.resolve(first), Some(default_accounts.alice));
+            assert_eq!(contract.resolve(second), Some(default_accounts.alice));
+            assert_eq!(contract.total_registrations(), 3);
+        }
+
+        #[ink::test]
+        fn admin_release_works() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            assert_eq!(contract.admin_release(name), Err(Error::UsernameNotFound));
+
+            set_next_caller(default_accounts.bob);
+            contract.register(name).unwrap();
+            contract.set_primary(name).unwrap();
+            contract
+                .set_operator(name, default_accounts.charlie)
+                .unwrap();
+            assert_eq!(contract.admin_release(name), Err(Error::NotAdmin));
+
+            set_next_caller(default_accounts.alice);
+            assert_eq!(contract.admin_release(name), Ok(()));
+            assert_eq!(contract.resolve(name), None);
+            assert_eq!(contract.get_name(default_accounts.bob), None);
+            assert_eq!(contract.primary_name_of(default_accounts.bob), None);
+            assert_eq!(contract.operator_of(name), None);
+            assert_eq!(contract.total_registrations(), 0);
+        }
+
+        #[ink::test]
+        fn freeze_works() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+            let new_name = Hash::from([0x02; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+
+            set_next_caller(default_accounts.bob);
+            contract.register(name).unwrap();
+            assert_eq!(contract.freeze(name), Err(Error::NotAdmin));
+
+            set_next_caller(default_accounts.alice);
+            assert_eq!(contract.freeze(name), Ok(()));
+            assert!(contract.is_frozen(name));
+
+            set_next_caller(default_accounts.bob);
+            assert_eq!(
+                contract.edit_username(name, new_name),
+                Err(Error::NameFrozen)
+            );
+            assert_eq!(
+                contract.transfer_name(name, default_accounts.charlie),
+                Err(Error::NameFrozen)
+            );
+            assert_eq!(contract.unregister(name), Err(Error::NameFrozen));
+            assert_eq!(contract.resolve(name), Some(default_accounts.bob));
+
+            set_next_caller(default_accounts.alice);
+            assert_eq!(contract.unfreeze(name), Ok(()));
+
+            set_next_caller(default_accounts.bob);
+            assert_eq!(contract.unregister(name), Ok(()));
+        }
+
+        #[ink::test]
+        fn release_lifts_freeze() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            contract.set_ttl(10).unwrap();
+
+            set_next_caller(default_accounts.bob);
+            contract.register(name).unwrap();
+            set_next_caller(default_accounts.alice);
+            contract.freeze(name).unwrap();
+            assert_eq!(contract.admin_release(name), Ok(()));
+            assert!(!contract.is_frozen(name));
+
+            set_next_caller(default_accounts.charlie);
+            contract.register(name).unwrap();
+            set_next_caller(default_accounts.alice);
+            contract.freeze(name).unwrap();
+            ink_env::test::advance_block::<Environment>();
+            ink_env::test::advance_block::<Environment>();
+
+            set_next_caller(default_accounts.django);
+            contract.register(name).unwrap();
+            assert!(!contract.is_frozen(name));
+            assert_eq!(contract.unregister(name), Ok(()));
+        }
+
+        #[ink::test]
+        fn edit_into_reserved_name_fails() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+            let reserved = Hash::from([0x02; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            contract.reserve(reserved).unwrap();
+
+            set_next_caller(default_accounts.bob);
+            contract.register(name).unwrap();
+            assert_eq!(
+                contract.edit_username(name, reserved),
+                Err(Error::NameReserved)
+            );
+            assert_eq!(
+                contract.edit_many(vec![(name, reserved)]),
+                Err(Error::NameReserved)
+            );
+            assert_eq!(contract.owner_of(reserved), None);
+        }
+
+        #[ink::test]
+        fn reserve_many_works() {
+            let default_accounts = default_accounts();
+            let first = Hash::from([0x01; 32]);
+            let second = Hash::from([0x02; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            contract.reserve(first).unwrap();
+            contract.set_max_batch(2).unwrap();
+            assert_eq!(
+                contract.reserve_many(vec![first, second, Hash::from([0x03; 32])]),
+                Err(Error::BatchTooLarge)
+            );
+            assert_eq!(contract.reserve_many(vec![first, second]), Ok(()));
+            assert!(contract.is_reserved(first));
+            assert!(contract.is_reserved(second));
+
+            let last = ink_env::test::recorded_events().last().unwrap();
+            assert!(matches!(
+                <Event as scale::Decode>::decode(&mut &last.data[..]),
+                Ok(Event::BulkReserved(BulkReserved { count: 1 }))
+            ));
+
+            set_next_caller(default_accounts.bob);
+            assert_eq!(contract.reserve_many(vec![]), Err(Error::NotAdmin));
+        }
+
+        #[ink::test]
+        fn reserve_works() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            assert_eq!(contract.reserve(name), Ok(()));
+            assert!(contract.is_reserved(name));
+
+            set_next_caller(default_accounts.bob);
+            assert_eq!(contract.register(name), Err(Error::NameReserved));
+            assert_eq!(contract.register_many(vec![name]), Err(Error::NameReserved));
+            assert_eq!(
+                contract.assign_reserved(name, default_accounts.bob),
+                Err(Error::NotAdmin)
+            );
+
+            set_next_caller(default_accounts.alice);
+            assert_eq!(contract.assign_reserved(name, default_accounts.bob), Ok(()));
+            assert_eq!(contract.resolve(name), Some(default_accounts.bob));
+
+            assert_eq!(contract.unreserve(name), Ok(()));
+            assert!(!contract.is_reserved(name));
+        }
+
+        #[ink::test]
+        fn rate_limit_works() {
+            let default_accounts = default_accounts();
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            assert_eq!(contract.set_rate_limit(6, 2), Ok(()));
+            assert_eq!(contract.rate_limit(), (6, 2));
+
+            set_next_caller(default_accounts.bob);
+            assert_eq!(contract.set_rate_limit(0, 0), Err(Error::NotAdmin));
+            contract.register(Hash::from([0x01; 32])).unwrap();
+            assert_eq!(
+                contract.register_many(vec![Hash::from([0x02; 32]), Hash::from([0x03; 32])]),
+                Err(Error::RateLimited)
+            );
+            contract.register(Hash::from([0x02; 32])).unwrap();
+            assert_eq!(
+                contract.register(Hash::from([0x03; 32])),
+                Err(Error::RateLimited)
+            );
+
+            set_next_caller(default_accounts.charlie);
+            assert_eq!(contract.register(Hash::from([0x03; 32])), Ok(2));
+
+            set_next_caller(default_accounts.bob);
+            ink_env::test::advance_block::<Environment>();
+            assert_eq!(
+                contract.register(Hash::from([0x04; 32])),
+                Err(Error::RateLimited)
+            );
+            ink_env::test::advance_block::<Environment>();
+            assert_eq!(contract.register(Hash::from([0x04; 32])), Ok(3));
+        }
+
+        #[ink::test]
+        fn edit_works() {
+            let default_accounts = default_accounts();
+            let old_name = Hash::from([0x01; 32]);
+            let new_name = Hash::from([0x02; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            contract.register(old_name).unwrap();
+
+            assert_eq!(contract.edit_username(old_name, new_name), Ok(()));
+            assert_eq!(contract.get_address(new_name), default_accounts.alice);
+            assert_eq!(contract.get_name(default_accounts.alice), Some(new_name));
+        }
+
+        #[ink::test]
+        fn edit_many_works() {
+            let default_accounts = default_accounts();
+            let first = Hash::from([0x01; 32]);
+            let second = Hash::from([0x02; 32]);
+            let third = Hash::from([0x03; 32]);
+            let fourth = Hash::from([0x04; 32]);
+            let taken = Hash::from([0x05; 32]);
+
+            set_next_caller(default_accounts.bob);
+            let mut contract = UkeHumanDns::new();
+            contract.register(taken).unwrap();
+
+            set_next_caller(default_accounts.alice);
+            contract.register_many(vec![first, second]).unwrap();
+            assert_eq!(
+                contract.edit_many(vec![(first, third), (taken, fourth)]),
+                Err(Error::CallerIsNotOwner)
+            );
+            assert_eq!(
+                contract.edit_many(vec![(first, third), (second, taken)]),
+                Err(Error::UsernameAlreadyExists)
+            );
+            assert_eq!(
+                contract.edit_many(vec![(first, third), (second, third)]),
+                Err(Error::UsernameAlreadyExists)
+            );
+            assert_eq!(contract.resolve(first), Some(default_accounts.alice));
+
+            set_next_caller(default_accounts.bob);
+            contract.set_max_batch(1).unwrap();
+            set_next_caller(default_accounts.alice);
+            assert_eq!(
+                contract.edit_many(vec![(first, third), (second, fourth)]),
+                Err(Error::BatchTooLarge)
+            );
+
+            set_next_caller(default_accounts.bob);
+            contract.set_max_batch(2).unwrap();
+            set_next_caller(default_accounts.alice);
+            assert_eq!(
+                contract.edit_many(vec![(first, third), (second, fourth)]),
+                Ok(())
+            );
+            assert_eq!(contract.resolve(first), None);
+            assert_eq!(contract.resolve(third), Some(default_accounts.alice));
+            assert_eq!(contract.resolve(fourth), Some(default_accounts.alice));
+        }
+
+        #[ink::test]
+        fn verify_owner_works() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x99; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            assert!(!contract.verify_owner(name, default_accounts.alice));
+
+            contract.register(name).unwrap();
+            assert!(contract.verify_owner(name, default_accounts.alice));
+            assert!(!contract.verify_owner(name, default_accounts.bob));
+
+            contract.transfer_name(name, default_accounts.bob).unwrap();
+            assert!(!contract.verify_owner(name, default_accounts.alice));
+            assert!(contract.verify_owner(name, default_accounts.bob));
+        }
+
+        #[ink::test]
+        fn version_and_supports_work() {
+            let contract = UkeHumanDns::new();
+            assert_eq!(contract.version(), VERSION);
+            assert!(contract.supports(FEATURE_TEXT_RECORDS));
+            assert!(contract.supports(FEATURE_EXPIRY));
+            assert!(contract.supports(FEATURE_REGISTRARS));
+            assert!(!contract.supports(0));
+            assert!(!contract.supports(FEATURE_REGISTRARS + 1));
+            assert!(!contract.supports(u32::MAX));
+        }
+
+        #[ink::test]
+        fn edit_unregistered_name_fails() {
+            let default_accounts = default_accounts();
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            assert_eq!(
+                contract.edit_username(Hash::from([0x01; 32]), Hash::from([0x02; 32])),
+                Err(Error::UsernameNotFound)
+            );
+        }
+
+        #[ink::test]
+        fn edit_into_taken_name_fails() {
+            let default_accounts = default_accounts();
+            let alice_name = Hash::from([0x01; 32]);
+            let bob_name = Hash::from([0x02; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            contract.register(alice_name).unwrap();
+
+            set_next_caller(default_accounts.bob);
+            contract.register(bob_name).unwrap();
+            assert_eq!(
+                contract.edit_username(bob_name, alice_name),
+                Err(Error::UsernameAlreadyExists)
+            );
+            assert_eq!(contract.resolve(alice_name), Some(default_accounts.alice));
+            assert_eq!(contract.owner_of(alice_name), Some(default_accounts.alice));
+            assert_eq!(contract.resolve(bob_name), Some(default_accounts.bob));
+            assert_eq!(contract.get_name(default_accounts.bob), Some(bob_name));
+        }
+
+        #[ink::test]
+        fn edit_to_same_name_fails() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            contract.register(name).unwrap();
+            assert_eq!(contract.edit_username(name, name), Err(Error::NoChange));
+            assert_eq!(contract.edit_many(vec![(name, name)]), Err(Error::NoChange));
+            assert_eq!(contract.resolve(name), Some(default_accounts.alice));
+            assert_eq!(contract.get_name(default_accounts.alice), Some(name));
+            assert_eq!(ink_env::test::recorded_events().count(), 1);
+        }
+
+        #[ink::test]
+        fn default_hash_is_rejected() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            assert_eq!(contract.register(Hash::default()), Err(Error::InvalidName));
+            assert_eq!(
+                contract.register_many(vec![name, Hash::default()]),
+                Err(Error::InvalidName)
+            );
+            assert_eq!(contract.resolve(name), None);
+
+            contract.register(name).unwrap();
+            assert_eq!(
+                contract.edit_username(name, Hash::default()),
+                Err(Error::InvalidName)
+            );
+            assert_eq!(contract.total_registrations(), 1);
+        }
+
+        #[ink::test]
+        fn subnames_work() {
+            let default_accounts = default_accounts();
+            let parent = Hash::from([0x01; 32]);
+            let label = Hash::from([0x02; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            assert_eq!(
+                contract.create_subname(parent, label, default_accounts.bob),
+                Err(Error::UsernameNotFound)
+            );
+            contract.register(parent).unwrap();
+
+            set_next_caller(default_accounts.bob);
+            assert_eq!(
+                contract.create_subname(parent, label, default_accounts.bob),
+                Err(Error::CallerIsNotOwner)
+            );
+
+            set_next_caller(default_accounts.alice);
+            assert_eq!(
+                contract.create_subname(parent, label, default_accounts.bob),
+                Ok(())
+            );
+            assert_eq!(
+                contract.resolve_subname(parent, label),
+                Some(default_accounts.bob)
+            );
+            assert_eq!(ink_env::test::recorded_events().count(), 2);
+
+            contract.unregister(parent).unwrap();
+            assert_eq!(contract.resolve_subname(parent, label), None);
+
+            contract.register(parent).unwrap();
+            assert_eq!(contract.resolve_subname(parent, label), None);
+        }
+
+        #[ink::test]
+        fn transfer_name_from_works() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            contract.register(name).unwrap();
+
+            set_next_caller(default_accounts.charlie);
+            assert_eq!(
+                contract.transfer_name_from(name, default_accounts.alice, default_accounts.bob),
+                Err(Error::NotAuthorized)
+            );
+
+            set_next_caller(default_accounts.alice);
+            assert_eq!(contract.approve(name, default_accounts.charlie), Ok(()));
+            assert_eq!(contract.approved_of(name), Some(default_accounts.charlie));
+
+            set_next_caller(default_accounts.charlie);
+            assert_eq!(
+                contract.transfer_name_from(name, default_accounts.bob, default_accounts.charlie),
+                Err(Error::CallerIsNotOwner)
+            );
+            assert_eq!(
+                contract.transfer_name_from(name, default_accounts.alice, default_accounts.bob),
+                Ok(())
+            );
+            assert_eq!(contract.owner_of(name), Some(default_accounts.bob));
+            assert_eq!(contract.approved_of(name), None);
+            assert_eq!(
+                contract.transfer_name_from(name, default_accounts.bob, default_accounts.charlie),
+                Err(Error::NotAuthorized)
+            );
+
+            set_next_caller(default_accounts.bob);
+            contract.approve(name, default_accounts.charlie).unwrap();
+            set_next_caller(default_accounts.charlie);
+            assert_eq!(
+                contract.transfer_name_from(name, default_accounts.bob, default_accounts.bob),
+                Ok(())
+            );
+            assert_eq!(contract.approved_of(name), None);
+        }
+
+        #[ink::test]
+        fn nonce_of_works() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+            let new_name = Hash::from([0x02; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            contract.register(name).unwrap();
+            assert_eq!(contract.nonce_of(name), 0);
+
+            contract
+                .set_text(name, b"bio".to_vec(), b"hi".to_vec())
+                .unwrap();
+            contract.set_address(name, default_accounts.django).unwrap();
+            assert_eq!(contract.nonce_of(name), 2);
+
+            contract.transfer_name(name, default_accounts.bob).unwrap();
+            assert_eq!(contract.nonce_of(name), 3);
+
+            set_next_caller(default_accounts.bob);
+            contract.edit_username(name, new_name).unwrap();
+            assert_eq!(contract.nonce_of(name), 4);
+            assert_eq!(contract.nonce_of(new_name), 1);
+
+            contract.unregister(new_name).unwrap();
+            assert_eq!(contract.nonce_of(new_name), 2);
+        }
+
+        #[ink::test]
+        fn register_signed_works() {
+            use secp256k1::{Message, SecretKey, SECP256K1};
+
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+            let secret = SecretKey::from_slice(&[0x42; 32]).unwrap();
+            let pubkey = secret.public_key(SECP256K1).serialize();
+            let mut owner = [0; 32];
+            ink_env::hash_bytes::<Blake2x256>(&pubkey, &mut owner);
+            let owner = AccountId::from(owner);
+            let sign = |message: Hash| {
+                let message = Message::from_slice(message.as_ref()).unwrap();
+                let (id, compact) = SECP256K1
+                    .sign_ecdsa_recoverable(&message, &secret)
+                    .serialize_compact();
+                let mut signature = [0; 65];
+                signature[..64].copy_from_slice(&compact);
+                signature[64] = id.to_i32() as u8;
+                signature
+            };
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            let signature = sign(contract.make_signed_registration(name, owner));
+            assert_eq!(
+                contract.register_signed(name, default_accounts.bob, signature),
+                Err(Error::BadSignature)
+            );
+
+            set_next_caller(default_accounts.charlie);
+            assert_eq!(contract.register_signed(name, owner, signature), Ok(()));
+            assert_eq!(contract.owner_of(name), Some(owner));
+            assert_eq!(contract.signing_nonce_of(owner), 1);
+
+            assert_eq!(
+                contract.register_signed(Hash::from([0x02; 32]), owner, signature),
+                Err(Error::BadSignature)
+            );
+        }
+
+        #[ink::test]
+        fn registered_between_works() {
+            let default_accounts = default_accounts();
+            let first = Hash::from([0x01; 32]);
+            let second = Hash::from([0x02; 32]);
+            let third = Hash::from([0x03; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            contract.register(first).unwrap();
+            ink_env::test::advance_block::<Environment>();
+            contract.register(second).unwrap();
+            ink_env::test::advance_block::<Environment>();
+            contract.register(third).unwrap();
+
+            assert_eq!(
+                contract.registered_between(0, 12, 0, 10),
+                vec![first, second, third]
+            );
+            assert_eq!(
+                contract.registered_between(6, 12, 0, 10),
+                vec![second, third]
+            );
+            assert_eq!(contract.registered_between(1, 5, 0, 10), Vec::<Hash>::new());
+            assert_eq!(contract.registered_between(0, 12, 1, 1), vec![second]);
+
+            ink_env::test::advance_block::<Environment>();
+            contract.renew(first).unwrap();
+            assert_eq!(contract.registered_between(0, 0, 0, 10), vec![first]);
+            assert_eq!(
+                contract.registered_between(18, 18, 0, 10),
+                Vec::<Hash>::new()
+            );
+            assert_eq!(contract.info(first).unwrap().registered_at, 0);
+        }
+
+        #[ink::test]
+        fn block_withholds_pubkey() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+            let key = [0x07; 32];
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            contract.register(name).unwrap();
+            contract.set_pubkey(key).unwrap();
+            assert_eq!(contract.block(default_accounts.bob), Ok(()));
+            assert!(contract.is_blocked(default_accounts.alice, default_accounts.bob));
+
+            set_next_caller(default_accounts.bob);
+            assert_eq!(
+                contract.resolve_full(name),
+                Some((default_accounts.alice, None))
+            );
+
+            set_next_caller(default_accounts.charlie);
+            assert_eq!(
+                contract.resolve_full(name),
+                Some((default_accounts.alice, Some(key)))
+            );
+
+            set_next_caller(default_accounts.alice);
+            contract.unblock(default_accounts.bob).unwrap();
+            set_next_caller(default_accounts.bob);
+            assert_eq!(
+                contract.resolve_full(name),
+                Some((default_accounts.alice, Some(key)))
+            );
+        }
+
+        #[ink::test]
+        fn migration_works() {
+            let default_accounts = default_accounts();
+            let first = Hash::from([0x01; 32]);
+            let second = Hash::from([0x02; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut old = UkeHumanDns::new();
+            old.register(first).unwrap();
+            set_next_caller(default_accounts.bob);
+            old.register(second).unwrap();
+            assert_eq!(old.export_chunk(0, 10), Err(Error::NotAdmin));
+            set_next_caller(default_accounts.alice);
+            let entries = old.export_chunk(0, 10).unwrap();
+            assert_eq!(
+                entries,
+                vec![
+                    (first, default_accounts.alice),
+                    (second, default_accounts.bob)
+                ]
+            );
+
+            ink_env::test::set_callee::<Environment>(default_accounts.django);
+            let mut new = UkeHumanDns::new();
+            assert!(new.is_migration_open());
+            assert_eq!(new.import_chunk(entries[..1].to_vec()), Ok(()));
+            assert_eq!(
+                new.import_chunk(entries.clone()),
+                Err(Error::UsernameAlreadyExists)
+            );
+            assert_eq!(new.owner_of(second), None);
+            assert_eq!(new.import_chunk(entries[1..].to_vec()), Ok(()));
+            assert_eq!(new.owner_of(first), Some(default_accounts.alice));
+            assert_eq!(new.owner_of(second), Some(default_accounts.bob));
+
+            new.register(Hash::from([0x03; 32])).unwrap();
+            assert!(!new.is_migration_open());
+            assert_eq!(
+                new.import_chunk(vec![(Hash::from([0x04; 32]), default_accounts.bob)]),
+                Err(Error::MigrationClosed)
+            );
+        }
+
+        #[ink::test]
+        fn set_code_requires_owner() {
+            let default_accounts = default_accounts();
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+
+            set_next_caller(default_accounts.bob);
+            assert_eq!(
+                contract.set_code(Hash::from([0x01; 32])),
+                Err(Error::NotAdmin)
+            );
+        }
+
+        #[ink::test]
+        fn recover_works() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            contract.register(name).unwrap();
+            contract
+                .set_operator(name, default_accounts.django)
+                .unwrap();
+
+            set_next_caller(default_accounts.bob);
+            assert_eq!(
+                contract.recover(name, default_accounts.charlie),
+                Err(Error::NotGuardian)
+            );
+
+            set_next_caller(default_accounts.alice);
+            contract.set_guardian(name, default_accounts.bob).unwrap();
+            assert_eq!(contract.guardian_of(name), Some(default_accounts.bob));
+
+            set_next_caller(default_accounts.bob);
+            assert_eq!(contract.recover(name, default_accounts.charlie), Ok(()));
+            assert_eq!(contract.owner_of(name), Some(default_accounts.charlie));
+            assert_eq!(contract.resolve(name), Some(default_accounts.charlie));
+            assert_eq!(contract.operator_of(name), None);
+            assert_eq!(contract.guardian_of(name), None);
+            assert_eq!(contract.get_name(default_accounts.alice), None);
+        }
+
+        #[ink::test]
+        fn wildcard_works() {
+            let default_accounts = default_accounts();
+            let parent = Hash::from([0x01; 32]);
+            let label = Hash::from([0x02; 32]);
+            let other = Hash::from([0x03; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            contract.register(parent).unwrap();
+            assert_eq!(contract.resolve_subname(parent, other), None);
+
+            set_next_caller(default_accounts.bob);
+            assert_eq!(
+                contract.set_wildcard(parent, default_accounts.bob),
+                Err(Error::CallerIsNotOwner)
+            );
+
+            set_next_caller(default_accounts.alice);
+            contract
+                .create_subname(parent, label, default_accounts.bob)
+                .unwrap();
+            assert_eq!(
+                contract.set_wildcard(parent, default_accounts.charlie),
+                Ok(())
+            );
+            assert_eq!(
+                contract.resolve_subname(parent, label),
+                Some(default_accounts.bob)
+            );
+            assert_eq!(
+                contract.resolve_subname(parent, other),
+                Some(default_accounts.charlie)
+            );
+
+            contract.unregister(parent).unwrap();
+            contract.register(parent).unwrap();
+            assert_eq!(contract.resolve_subname(parent, other), None);
+        }
+
+        #[ink::test]
+        fn resolve_batch_works() {
+            let default_accounts = default_accounts();
+            let first = Hash::from([0x01; 32]);
+            let second = Hash::from([0x02; 32]);
+            let unknown = Hash::from([0x03; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            contract.register(first).unwrap();
+            set_next_caller(default_accounts.bob);
+            contract.register(second).unwrap();
+
+            assert_eq!(
+                contract.resolve_batch(vec![second, unknown, first]),
+                vec![
+                    Some(default_accounts.bob),
+                    None,
+                    Some(default_accounts.alice)
+                ]
+            );
+            assert_eq!(
+                contract
+                    .resolve_batch(vec![first; MAX_PAGE_SIZE as usize + 1])
+                    .len(),
+                MAX_PAGE_SIZE as usize
+            );
+        }
+
+        #[ink::test]
+        fn get_name_batch_works() {
+            let default_accounts = default_accounts();
+            let first = Hash::from([0x01; 32]);
+            let second = Hash::from([0x02; 32]);
+            let third = Hash::from([0x03; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            contract.register(first).unwrap();
+            contract.register(second).unwrap();
+            contract.set_primary(second).unwrap();
+            set_next_caller(default_accounts.bob);
+            contract.register(third).unwrap();
+
+            assert_eq!(
+                contract.get_name_batch(vec![
+                    default_accounts.bob,
+                    default_accounts.charlie,
+                    default_accounts.alice
+                ]),
+                vec![Some(third), None, Some(second)]
+            );
+        }
+
+        #[ink::test]
+        fn contract_target_works() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+            let renamed = Hash::from([0x02; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            contract.register(name).unwrap();
+            assert!(!contract.target_is_contract(name));
+
+            contract.set_address(name, default_accounts.django).unwrap();
+            assert_eq!(contract.set_contract_target(name, true), Ok(()));
+            assert!(contract.target_is_contract(name));
+            assert_eq!(contract.resolve(name), Some(default_accounts.django));
+
+            contract.edit_username(name, renamed).unwrap();
+            assert!(contract.target_is_contract(renamed));
+
+            contract.set_address(renamed, default_accounts.eve).unwrap();
+            assert!(!contract.target_is_contract(renamed));
+
+            set_next_caller(default_accounts.bob);
+            assert_eq!(
+                contract.set_contract_target(renamed, true),
+                Err(Error::CallerIsNotOwner)
+            );
+        }
+
+        #[ink::test]
+        fn is_available_works() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+            let reserved = Hash::from([0x02; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            contract.set_ttl(10).unwrap();
+            contract.reserve(reserved).unwrap();
+            assert!(contract.is_available(name));
+            assert!(!contract.is_available(reserved));
+            assert!(!contract.is_available(Hash::default()));
+
+            contract.register(name).unwrap();
+            assert!(!contract.is_available(name));
+
+            ink_env::test::advance_block::<Environment>();
+            ink_env::test::advance_block::<Environment>();
+            assert!(contract.is_available(name));
+        }
+
+        #[ink::test]
+        fn register_for_works() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+
+            set_next_caller(default_accounts.bob);
+            assert_eq!(
+                contract.register_for(name, default_accounts.charlie),
+                Err(Error::NotAuthorized)
+            );
+
+            set_next_caller(default_accounts.alice);
+            assert_eq!(
+                contract.register_for(name, default_accounts.charlie),
+                Ok(())
+            );
+            assert_eq!(contract.owner_of(name), Some(default_accounts.charlie));
+            assert_eq!(contract.get_name(default_accounts.alice), None);
+        }
+
+        #[ink::test]
+        fn registrars_work() {
+            let default_accounts = default_accounts();
+            let first = Hash::from([0x01; 32]);
+            let second = Hash::from([0x02; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+
+            set_next_caller(default_accounts.bob);
+            assert_eq!(
+                contract.add_registrar(default_accounts.bob),
+                Err(Error::NotAdmin)
+            );
+
+            set_next_caller(default_accounts.alice);
+            assert_eq!(contract.add_registrar(default_accounts.bob), Ok(()));
+            assert!(contract.is_registrar(default_accounts.bob));
+
+            set_next_caller(default_accounts.bob);
+            assert_eq!(
+                contract.register_for(first, default_accounts.charlie),
+                Ok(())
+            );
+            assert_eq!(contract.owner_of(first), Some(default_accounts.charlie));
+
+            set_next_caller(default_accounts.alice);
+            assert_eq!(contract.remove_registrar(default_accounts.bob), Ok(()));
+            assert!(!contract.is_registrar(default_accounts.bob));
+
+            set_next_caller(default_accounts.bob);
+            assert_eq!(
+                contract.register_for(second, default_accounts.charlie),
+                Err(Error::NotAuthorized)
+            );
+            assert_eq!(ink_env::test::recorded_events().count(), 3);
+        }
+
+        #[ink::test]
+        fn try_get_address_works() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            assert_eq!(contract.try_get_address(name), Err(Error::UsernameNotFound));
+
+            contract.register(name).unwrap();
+            assert_eq!(contract.try_get_address(name), Ok(default_accounts.alice));
+        }
+
+        #[ink::test]
+        fn meta_version_works() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            assert_eq!(contract.meta_version_of(name), 0);
+
+            contract.register(name).unwrap();
+            assert_eq!(contract.meta_version_of(name), 1);
+
+            contract.set_addr(name, 0, b"bc1q".to_vec()).unwrap();
+            contract.set_contract_target(name, true).unwrap();
+            assert_eq!(contract.meta_version_of(name), 3);
+
+            contract.transfer_name(name, default_accounts.bob).unwrap();
+            assert_eq!(contract.meta_version_of(name), 4);
+
+            contract.set_pubkey([0x01; 32]).unwrap();
+            assert_eq!(contract.meta_version_of(name), 4);
+        }
+
+        #[ink::test]
+        fn export_sorted_works() {
+            let default_accounts = default_accounts();
+            let low = Hash::from([0x01; 32]);
+            let mid = Hash::from([0x02; 32]);
+            let high = Hash::from([0x03; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            contract.register(high).unwrap();
+            contract.register(low).unwrap();
+            set_next_caller(default_accounts.bob);
+            contract.register(mid).unwrap();
+
+            assert_eq!(
+                contract.export_sorted(0, 10),
+                vec![
+                    (low, default_accounts.alice),
+                    (mid, default_accounts.bob),
+                    (high, default_accounts.alice)
+                ]
+            );
+            assert_eq!(
+                contract.export_sorted(1, 1),
+                vec![(mid, default_accounts.bob)]
+            );
+        }
+
+        #[ink::test]
+        fn set_default_address_works() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            assert_eq!(contract.get_address(name), AccountId::default());
+            assert_eq!(contract.set_default_address(default_accounts.eve), Ok(()));
+            assert_eq!(contract.default_address(), default_accounts.eve);
+            assert_eq!(contract.get_address(name), default_accounts.eve);
+            assert_eq!(contract.resolve(name), None);
+
+            set_next_caller(default_accounts.bob);
+            assert_eq!(
+                contract.set_default_address(default_accounts.bob),
+                Err(Error::NotAdmin)
+            );
+        }
+
+        #[ink::test]
+        fn content_hash_works() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+            let renamed = Hash::from([0x02; 32]);
+            let cid = b"bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi".to_vec();
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            contract.register(name).unwrap();
+            assert_eq!(contract.get_content_hash(name), None);
+            assert_eq!(
+                contract.set_content_hash(name, Vec::new()),
+                Err(Error::EmptyValue)
+            );
+            assert_eq!(
+                contract.set_content_hash(name, [0x01; MAX_CONTENT_HASH_LEN + 1].to_vec()),
+                Err(Error::ValueTooLong)
+            );
+            assert_eq!(contract.set_content_hash(name, cid.clone()), Ok(()));
+            assert_eq!(contract.get_content_hash(name), Some(cid.clone()));
+
+            contract.edit_username(name, renamed).unwrap();
+            assert_eq!(contract.get_content_hash(name), None);
+            assert_eq!(contract.get_content_hash(renamed), Some(cid.clone()));
+
+            set_next_caller(default_accounts.bob);
+            assert_eq!(
+                contract.set_content_hash(renamed, cid),
+                Err(Error::CallerIsNotOwner)
+            );
+
+            set_next_caller(default_accounts.alice);
+            contract.unregister(renamed).unwrap();
+            assert_eq!(contract.get_content_hash(renamed), None);
+        }
+
+        #[ink::test]
+        fn get_address_works() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+
+            assert_eq!(contract.register(name), Ok(0));
+            assert_eq!(contract.get_address(name), default_accounts.alice);
+        }
+
+        #[ink::test]
+        fn unregister_works() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            assert_eq!(contract.unregister(name), Err(Error::UsernameNotFound));
+            contract.register(name).unwrap();
+
+            set_next_caller(default_accounts.bob);
+            assert_eq!(contract.unregister(name), Err(Error::CallerIsNotOwner));
+
+            set_next_caller(default_accounts.alice);
+            assert_eq!(contract.unregister(name), Ok(()));
+            assert_eq!(contract.get_name(default_accounts.alice), None);
+            assert_eq!(contract.get_address(name), AccountId::default());
+
+            set_next_caller(default_accounts.bob);
+            assert_eq!(contract.register(name), Ok(1));
+        }
+
+        #[ink::test]
+        fn registration_fee_works() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            assert_eq!(contract.fee(), 0);
+            assert_eq!(contract.set_fee(100), Ok(()));
+
+            set_next_caller(default_accounts.bob);
+            assert_eq!(contract.set_fee(0), Err(Error::NotAdmin));
+            ink_env::test::set_value_transferred::<Environment>(99);
+            assert_eq!(contract.register(name), Err(Error::InsufficientFee));
+            ink_env::test::set_value_transferred::<Environment>(100);
+            assert_eq!(contract.register(name), Ok(0));
+            assert_eq!(contract.resolve(name), Some(default_accounts.bob));
+        }
+
+        #[ink::test]
+        fn length_pricing_works() {
+            let default_accounts = default_accounts();
+            let short = Hash::from([0x01; 32]);
+            let long = Hash::from([0x02; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            contract.set_fee(10).unwrap();
+            assert_eq!(contract.set_length_price(3, 1_000), Ok(()));
+            assert_eq!(contract.set_length_price(8, 5), Ok(()));
+            assert_eq!(contract.price_for_len(3), 1_000);
+            assert_eq!(contract.price_for_len(4), 10);
+            assert_eq!(contract.price_for_len(20), 5);
+
+            set_next_caller(default_accounts.bob);
+            assert_eq!(contract.set_length_price(3, 0), Err(Error::NotAdmin));
+            ink_env::test::set_value_transferred::<Environment>(999);
+            assert_eq!(
+                contract.register_with_len(short, 3),
+                Err(Error::InsufficientFee)
+            );
+            ink_env::test::set_value_transferred::<Environment>(1_000);
+            assert_eq!(contract.register_with_len(short, 3), Ok(()));
+            ink_env::test::set_value_transferred::<Environment>(5);
+            assert_eq!(contract.register_with_len(long, 12), Ok(()));
+        }
+
+        #[ink::test]
+        fn hash_only_registration_pays_highest_tier() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            contract.set_fee(10).unwrap();
+            assert_eq!(contract.hash_only_price(), 10);
+            contract.set_length_price(3, 1_000).unwrap();
+            contract.set_length_price(8, 5).unwrap();
+            assert_eq!(contract.hash_only_price(), 1_000);
+
+            set_next_caller(default_accounts.bob);
+            ink_env::test::set_value_transferred::<Environment>(10);
+            assert_eq!(contract.register(name), Err(Error::InsufficientFee));
+            assert_eq!(
+                contract.register_many(vec![name]),
+                Err(Error::InsufficientFee)
+            );
+            ink_env::test::set_value_transferred::<Environment>(1_000);
+            assert_eq!(contract.register(name), Ok(0));
+
+            ink_env::test::set_value_transferred::<Environment>(10);
+            assert_eq!(contract.renew(name), Ok(()));
+        }
+
+        #[ink::test]
+        fn withdraw_works() {
+            let default_accounts = default_accounts();
+
+            set_next_caller(default_accounts.alice);
+            ink_env::test::set_callee::<Environment>(default_accounts.django);
+            let mut contract = UkeHumanDns::new();
+            ink_env::test::set_account_balance::<Environment>(contract_id(), 500);
+
+            assert_eq!(contract.withdraw(501), Err(Error::InsufficientBalance));
+            assert_eq!(contract.withdraw(200), Ok(()));
+            assert_eq!(balance_of(contract_id()), 300);
+            assert_eq!(balance_of(default_accounts.alice), 1_000_200);
+
+            set_next_caller(default_accounts.bob);
+            assert_eq!(contract.withdraw(100), Err(Error::NotAdmin));
+        }
+
+        #[ink::test]
+        fn fee_accounting_works() {
+            let default_accounts = default_accounts();
+
+            set_next_caller(default_accounts.alice);
+            ink_env::test::set_callee::<Environment>(default_accounts.django);
+            let mut contract = UkeHumanDns::new();
+            contract.set_fee(100).unwrap();
+            ink_env::test::set_account_balance::<Environment>(contract_id(), 500);
+            assert_eq!(contract.contract_balance(), 500);
+
+            ink_env::test::set_value_transferred::<Environment>(99);
+            assert_eq!(
+                contract.register(Hash::from([0x01; 32])),
+                Err(Error::InsufficientFee)
+            );
+            assert_eq!(contract.total_fees(), 0);
+
+            ink_env::test::set_value_transferred::<Environment>(100);
+            contract.register(Hash::from([0x01; 32])).unwrap();
+            ink_env::test::set_value_transferred::<Environment>(200);
+            contract
+                .register_many(vec![Hash::from([0x02; 32]), Hash::from([0x03; 32])])
+                .unwrap();
+            assert_eq!(contract.total_fees(), 300);
+
+            contract.withdraw(200).unwrap();
+            assert_eq!(contract.contract_balance(), 300);
+            assert_eq!(contract.total_fees(), 300);
+        }
+
+        #[ink::test]
+        fn min_hold_works() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            assert_eq!(contract.min_hold(), 0);
+            assert_eq!(contract.set_min_hold(6), Ok(()));
+            contract.register(name).unwrap();
+            assert_eq!(contract.unregister(name), Err(Error::MinHoldNotMet));
+            assert_eq!(
+                contract.transfer_name(name, default_accounts.bob),
+                Err(Error::MinHoldNotMet)
+            );
+
+            ink_env::test::advance_block::<Environment>();
+            assert_eq!(contract.renew(name), Ok(()));
+            assert_eq!(contract.unregister(name), Ok(()));
+
+            set_next_caller(default_accounts.bob);
+            assert_eq!(contract.set_min_hold(0), Err(Error::NotAdmin));
+        }
+
+        #[ink::test]
+        fn min_hold_covers_all_ownership_changes() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            contract.set_min_hold(6).unwrap();
+            contract.register(name).unwrap();
+            contract.offer_name(name, default_accounts.bob).unwrap();
+            contract.list_for_sale(name, 0).unwrap();
+            contract
+                .set_guardian(name, default_accounts.charlie)
+                .unwrap();
+            assert_eq!(contract.start_auction(name, 60), Err(Error::MinHoldNotMet));
+
+            set_next_caller(default_accounts.bob);
+            assert_eq!(contract.claim_name(name), Err(Error::MinHoldNotMet));
+            assert_eq!(contract.buy(name), Err(Error::MinHoldNotMet));
+
+            set_next_caller(default_accounts.charlie);
+            assert_eq!(contract.recover(name, default_accounts.django), Ok(()));
+            assert_eq!(contract.owner_of(name), Some(default_accounts.django));
+
+            ink_env::test::advance_block::<Environment>();
+            set_next_caller(default_accounts.django);
+            contract.offer_name(name, default_accounts.bob).unwrap();
+            set_next_caller(default_accounts.bob);
+            assert_eq!(contract.claim_name(name), Ok(()));
+        }
+
+        #[ink::test]
+        fn info_works() {
+            let default_accounts = default_accounts();
+            let first = Hash::from([0x01; 32]);
+            let second = Hash::from([0x02; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            assert_eq!(contract.info(first), None);
+
+            contract.register(first).unwrap();
+            ink_env::test::advance_block::<Environment>();
+            contract.register(second).unwrap();
+            contract.set_address(second, default_accounts.bob).unwrap();
+            assert_eq!(
+                contract.info(second),
+                Some(RegistrationInfo {
+                    owner: default_accounts.alice,
+                    target: default_accounts.bob,
+                    id: 1,
+                    registered_at: 6,
+                    is_primary: false,
+                    expired: false,
+                })
+            );
+            assert!(contract.info(first).unwrap().is_primary);
+        }
+
+        #[ink::test]
+        fn state_root_hint_works() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+            let mix = |previous: Hash, registered: bool| {
+                let mut output = [0; 32];
+                ink_env::hash_encoded::<Blake2x256, _>(&(previous, name, registered), &mut output);
+                Hash::from(output)
+            };
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            assert_eq!(contract.state_root_hint(), (0, Hash::default()));
+
+            contract.register(name).unwrap();
+            let registered = mix(Hash::default(), true);
+            assert_eq!(contract.state_root_hint(), (1, registered));
+
+            contract.unregister(name).unwrap();
+            assert_eq!(contract.state_root_hint(), (0, mix(registered, false)));
+        }
+
+        #[ink::test]
+        fn last_mutation_block_works() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            assert_eq!(contract.last_mutation_block(), 0);
+
+            ink_env::test::advance_block::<Environment>();
+            contract.register(name).unwrap();
+            assert_eq!(contract.last_mutation_block(), 1);
+
+            ink_env::test::advance_block::<Environment>();
+            assert_eq!(
+                contract.unregister(Hash::from([0x02; 32])),
+                Err(Error::UsernameNotFound)
+            );
+            assert_eq!(contract.last_mutation_block(), 1);
+            contract.set_fee(1).unwrap();
+            assert_eq!(contract.last_mutation_block(), 2);
+
+            contract.start_auction(name, 18).unwrap();
+            ink_env::test::advance_block::<Environment>();
+            assert_eq!(contract.settle_auction(name), Ok(()));
+            assert_eq!(contract.last_mutation_block(), 3);
+        }
+
+        #[ink::test]
+        fn new_configured_works() {
+            let default_accounts = default_accounts();
+
+            set_next_caller(default_accounts.alice);
+            let contract = UkeHumanDns::new_configured(10, 100, 2);
+            assert_eq!(contract.owner(), default_accounts.alice);
+            assert_eq!(contract.fee(), 10);
+            assert_eq!(contract.ttl(), 100);
+            assert_eq!(contract.max_names_per_account(), 2);
+            assert_eq!(contract.max_batch(), DEFAULT_MAX_BATCH);
+        }
+
+        #[ink::test]
+        fn config_works() {
+            let default_accounts = default_accounts();
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new_configured(10, 100, 2);
+            contract.set_grace_period(5).unwrap();
+            contract.set_rate_limit(60, 3).unwrap();
+            assert_eq!(
+                contract.config(),
+                Config {
+                    fee: 10,
+                    ttl: 100,
+                    grace_period: 5,
+                    min_commitment_age: 0,
+                    min_hold: 0,
+                    max_batch: DEFAULT_MAX_BATCH,
+                    max_text_len: DEFAULT_MAX_TEXT_LEN,
+                    max_names_per_account: 2,
+                    rate_window: 60,
+                    max_per_window: 3,
+                    charset_policy: CharsetPolicy::AlphaNumDash,
+                    paused: false,
+                }
+            );
+        }
+
+        #[ink::test]
+        fn transfer_to_zero_account_unregisters() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            contract.register(name).unwrap();
+            assert_eq!(contract.transfer_name(name, AccountId::default()), Ok(()));
+            assert_eq!(contract.owner_of(name), None);
+            assert_eq!(contract.resolve(name), None);
+            assert_eq!(contract.total_registrations(), 0);
+            assert_eq!(contract.names_of(AccountId::default()), Vec::<Hash>::new());
+
+            let last = ink_env::test::recorded_events().last().unwrap();
+            assert!(matches!(
+                <Event as scale::Decode>::decode(&mut &last.data[..]),
+                Ok(Event::Unregister(_))
+            ));
+
+            set_next_caller(default_accounts.bob);
+            assert_eq!(contract.register(name), Ok(1));
+            contract.approve(name, default_accounts.charlie).unwrap();
+            contract
+                .set_guardian(name, default_accounts.django)
+                .unwrap();
+
+            set_next_caller(default_accounts.django);
+            assert_eq!(
+                contract.recover(name, AccountId::default()),
+                Err(Error::InvalidRecipient)
+            );
+            assert_eq!(contract.owner_of(name), Some(default_accounts.bob));
+
+            set_next_caller(default_accounts.charlie);
+            assert_eq!(
+                contract.transfer_name_from(name, default_accounts.bob, AccountId::default()),
+                Ok(())
+            );
+            assert_eq!(contract.owner_of(name), None);
+            assert_eq!(contract.total_registrations(), 0);
+        }
+
+        #[ink::test]
+        fn state_changed_events_work() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+            let new_name = Hash::from([0x02; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            assert!(!contract.state_changed_events());
+            contract.register(name).unwrap();
+            assert_eq!(ink_env::test::recorded_events().count(), 1);
+
+            assert_eq!(contract.set_state_changed_events(true), Ok(()));
+            contract.edit_username(name, new_name).unwrap();
+            contract
+                .transfer_name(new_name, default_accounts.bob)
+                .unwrap();
+            set_next_caller(default_accounts.bob);
+            contract.unregister(new_name).unwrap();
+            assert_eq!(
+                contract.set_state_changed_events(false),
+                Err(Error::NotAdmin)
+            );
+
+            let kinds: Vec<u8> = ink_env::test::recorded_events()
+                .filter_map(
+                    |event| match <Event as scale::Decode>::decode(&mut &event.data[..]) {
+                        Ok(Event::StateChanged(StateChanged { name, kind })) => {
+                            assert_eq!(name, new_name);
+                            Some(kind)
+                        }
+                        _ => None,
+                    },
+                )
+                .collect();
+            assert_eq!(kinds, vec![KIND_EDIT, KIND_TRANSFER, KIND_RELEASE]);
+            assert_eq!(ink_env::test::recorded_events().count(), 7);
+        }
+
+        #[ink::test]
+        fn emit_events_works() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+            let new_name = Hash::from([0x02; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            assert!(contract.emit_events());
+            assert_eq!(contract.set_emit_events(false), Ok(()));
+            assert!(!contract.emit_events());
+
+            contract.register(name).unwrap();
+            contract.edit_username(name, new_name).unwrap();
+            assert_eq!(ink_env::test::recorded_events().count(), 0);
+            assert_eq!(contract.lookup(new_name), Some(default_accounts.alice));
+
+            set_next_caller(default_accounts.bob);
+            assert_eq!(contract.set_emit_events(true), Err(Error::NotAdmin));
+        }
+
+        #[ink::test]
+        fn max_names_per_account_works() {
+            let default_accounts = default_accounts();
+            let first = Hash::from([0x01; 32]);
+            let second = Hash::from([0x02; 32]);
+            let third = Hash::from([0x03; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            assert_eq!(contract.set_max_names_per_account(1), Ok(()));
+            contract.register(first).unwrap();
+            assert_eq!(contract.register(second), Err(Error::TooManyNames));
+
+            set_next_caller(default_accounts.bob);
+            assert_eq!(
+                contract.register_many(vec![second, third]),
+                Err(Error::TooManyNames)
+            );
+            contract.register(second).unwrap();
+            assert_eq!(
+                contract.transfer_name(second, default_accounts.alice),
+                Err(Error::TooManyNames)
+            );
+            assert_eq!(contract.owner_of(second), Some(default_accounts.bob));
+
+            set_next_caller(default_accounts.alice);
+            contract.unregister(first).unwrap();
+            set_next_caller(default_accounts.bob);
+            assert_eq!(
+                contract.transfer_name(second, default_accounts.alice),
+                Ok(())
+            );
+            assert_eq!(contract.register(third), Ok(2));
+        }
+
+        #[ink::test]
+        fn buy_works() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+
+            set_next_caller(default_accounts.alice);
+            ink_env::test::set_callee::<Environment>(default_accounts.django);
+            let mut contract = UkeHumanDns::new();
+            contract.register(name).unwrap();
+
+            set_next_caller(default_accounts.bob);
+            assert_eq!(contract.buy(name), Err(Error::NotForSale));
+
+            set_next_caller(default_accounts.alice);
+            assert_eq!(contract.list_for_sale(name, 500), Ok(()));
+            assert_eq!(contract.listing_of(name), Some(500));
+
+            set_next_caller(default_accounts.bob);
+            ink_env::test::set_value_transferred::<Environment>(499);
+            assert_eq!(contract.buy(name), Err(Error::InsufficientFunds));
+
+            ink_env::test::set_account_balance::<Environment>(contract_id(), 500);
+            ink_env::test::set_value_transferred::<Environment>(500);
+            let seller_balance = balance_of(default_accounts.alice);
+            assert_eq!(contract.buy(name), Ok(()));
+            assert_eq!(contract.owner_of(name), Some(default_accounts.bob));
+            assert_eq!(contract.listing_of(name), None);
+            assert_eq!(balance_of(default_accounts.alice), seller_balance + 500);
+        }
+
+        #[ink::test]
+        fn auction_works() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+
+            set_next_caller(default_accounts.alice);
+            ink_env::test::set_callee::<Environment>(default_accounts.django);
+            let mut contract = UkeHumanDns::new();
+            contract.register(name).unwrap();
+            assert_eq!(contract.bid(name), Err(Error::NoAuction));
+            assert_eq!(contract.start_auction(name, 0), Err(Error::AuctionEnded));
+            assert_eq!(contract.start_auction(name, 12), Ok(()));
+
+            set_next_caller(default_accounts.bob);
+            ink_env::test::set_account_balance::<Environment>(contract_id(), 100);
+            ink_env::test::set_value_transferred::<Environment>(100);
+            assert_eq!(contract.bid(name), Ok(()));
+
+            set_next_caller(default_accounts.charlie);
+            assert_eq!(contract.bid(name), Err(Error::BidTooLow));
+            ink_env::test::set_account_balance::<Environment>(contract_id(), 250);
+            ink_env::test::set_value_transferred::<Environment>(150);
+            assert_eq!(contract.bid(name), Ok(()));
+            assert_eq!(contract.pending_withdrawal_of(default_accounts.bob), 100);
+            assert_eq!(contract.settle_auction(name), Err(Error::AuctionNotEnded));
+
+            ink_env::test::advance_block::<Environment>();
+            ink_env::test::advance_block::<Environment>();
+            assert_eq!(contract.bid(name), Err(Error::AuctionEnded));
+
+            let alice_balance = balance_of(default_accounts.alice);
+            assert_eq!(contract.settle_auction(name), Ok(()));
+            assert_eq!(contract.owner_of(name), Some(default_accounts.charlie));
+            assert_eq!(balance_of(default_accounts.alice), alice_balance + 150);
+            assert_eq!(contract.auction_of(name), None);
+
+            set_next_caller(default_accounts.bob);
+            let bob_balance = balance_of(default_accounts.bob);
+            assert_eq!(contract.withdraw_refund(), Ok(()));
+            assert_eq!(balance_of(default_accounts.bob), bob_balance + 100);
+            assert_eq!(contract.pending_withdrawal_of(default_accounts.bob), 0);
+            assert_eq!(contract.withdraw_refund(), Err(Error::NothingToWithdraw));
+        }
+
+        #[ink::test]
+        fn auction_dropped_on_owner_change() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+
+            set_next_caller(default_accounts.alice);
+            ink_env::test::set_callee::<Environment>(default_accounts.django);
+            let mut contract = UkeHumanDns::new();
+            contract.register(name).unwrap();
+            contract.start_auction(name, 12).unwrap();
+            assert_eq!(contract.start_auction(name, 12), Err(Error::AuctionRunning));
+
+            set_next_caller(default_accounts.bob);
+            ink_env::test::set_account_balance::<Environment>(contract_id(), 100);
+            ink_env::test::set_value_transferred::<Environment>(100);
+            contract.bid(name).unwrap();
+
+            set_next_caller(default_accounts.alice);
+            assert_eq!(
+                contract.transfer_name(name, default_accounts.charlie),
+                Ok(())
+            );
+            assert_eq!(contract.auction_of(name), None);
+            assert_eq!(contract.pending_withdrawal_of(default_accounts.bob), 100);
+
+            set_next_caller(default_accounts.eve);
+            ink_env::test::set_value_transferred::<Environment>(200);
+            assert_eq!(contract.bid(name), Err(Error::NoAuction));
+
+            set_next_caller(default_accounts.charlie);
+            assert_eq!(contract.start_auction(name, 12), Ok(()));
+            assert_eq!(contract.unregister(name), Ok(()));
+            assert_eq!(contract.auction_of(name), None);
+        }
+
+        #[ink::test]
+        fn withdraw_keeps_escrow() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+
+            set_next_caller(default_accounts.alice);
+            ink_env::test::set_callee::<Environment>(default_accounts.django);
+            let mut contract = UkeHumanDns::new();
+            contract.register(name).unwrap();
+            contract.start_auction(name, 12).unwrap();
+
+            set_next_caller(default_accounts.bob);
+            ink_env::test::set_account_balance::<Environment>(contract_id(), 300);
+            ink_env::test::set_value_transferred::<Environment>(100);
+            contract.bid(name).unwrap();
+
+            set_next_caller(default_accounts.alice);
+            assert_eq!(contract.withdraw(201), Err(Error::InsufficientBalance));
+            assert_eq!(contract.withdraw(200), Ok(()));
+        }
+
+        #[ink::test]
+        fn value_transfers_reject_reentrancy() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+
+            set_next_caller(default_accounts.alice);
+            ink_env::test::set_callee::<Environment>(default_accounts.django);
+            let mut contract = UkeHumanDns::new();
+            ink_env::test::set_account_balance::<Environment>(contract_id(), 500);
+            contract.register(name).unwrap();
+            contract.list_for_sale(name, 0).unwrap();
+
+            // Simulate a callback arriving while a payout is in progress.
+            contract.locked.insert((), &());
+            assert_eq!(contract.withdraw(100), Err(Error::Reentrancy));
+            set_next_caller(default_accounts.bob);
+            assert_eq!(contract.buy(name), Err(Error::Reentrancy));
+            assert_eq!(contract.owner_of(name), Some(default_accounts.alice));
+            assert_eq!(balance_of(contract_id()), 500);
+
+            contract.locked.remove(());
+            assert_eq!(contract.buy(name), Ok(()));
+            assert!(!contract.locked.contains(()));
+        }
+
+        #[ink::test]
+        fn total_registrations_works() {
+            let default_accounts = default_accounts();
+            let first = Hash::from([0x01; 32]);
+            let second = Hash::from([0x02; 32]);
+            let renamed = Hash::from([0x03; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            assert_eq!(contract.total_registrations(), 0);
+
+            contract.register(first).unwrap();
+            contract.register(second).unwrap();
+            assert_eq!(contract.total_registrations(), 2);
+
+            contract.edit_username(second, renamed).unwrap();
+            assert_eq!(contract.total_registrations(), 2);
+
+            contract.unregister(first).unwrap();
+            assert_eq!(contract.total_registrations(), 1);
+        }
+
+        #[ink::test]
+        fn names_paged_works() {
+            let default_accounts = default_accounts();
+            let first = Hash::from([0x01; 32]);
+            let second = Hash::from([0x02; 32]);
+            let third = Hash::from([0x03; 32]);
+            let renamed = Hash::from([0x04; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            assert_eq!(contract.names_paged(0, 10), vec![]);
+            contract.register_many(vec![first, second, third]).unwrap();
+            assert_eq!(contract.names_paged(0, 10), vec![first, second, third]);
+            assert_eq!(contract.names_paged(1, 1), vec![second]);
+            assert_eq!(contract.names_paged(5, 10), vec![]);
+
+            contract.unregister(first).unwrap();
+            assert_eq!(contract.names_paged(0, 10), vec![third, second]);
+
+            contract.edit_username(second, renamed).unwrap();
+            assert_eq!(contract.names_paged(0, 10), vec![third, renamed]);
+
+            contract.unregister(renamed).unwrap();
+            contract.unregister(third).unwrap();
+            assert_eq!(contract.names_paged(0, 10), vec![]);
+        }
+
+        #[ink::test]
+        fn names_of_works() {
+            let default_accounts = default_accounts();
+            let first = Hash::from([0x01; 32]);
+            let second = Hash::from([0x02; 32]);
+            let third = Hash::from([0x03; 32]);
+            let renamed = Hash::from([0x04; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            assert_eq!(contract.names_of(default_accounts.alice), vec![]);
+            contract.register_many(vec![first, second, third]).unwrap();
+            assert_eq!(
+                contract.names_of(default_accounts.alice),
+                vec![first, second, third]
+            );
+
+            contract.transfer_name(first, default_accounts.bob).unwrap();
+            assert_eq!(
+                contract.names_of(default_accounts.alice),
+                vec![third, second]
+            );
+            assert_eq!(
+                contract.primary_name_of(default_accounts.alice),
+                Some(second)
+            );
+            assert_eq!(contract.names_of(default_accounts.bob), vec![first]);
+
+            contract.edit_username(second, renamed).unwrap();
+            contract.unregister(third).unwrap();
+            assert_eq!(contract.names_of(default_accounts.alice), vec![renamed]);
+        }
+
+        #[ink::test]
+        fn transfer_name_works() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            assert_eq!(
+                contract.transfer_name(name, default_accounts.bob),
+                Err(Error::UsernameNotFound)
+            );
+            contract.register(name).unwrap();
+            assert_eq!(contract.transfer_name(name, default_accounts.alice), Ok(()));
+            assert_eq!(contract.resolve(name), Some(default_accounts.alice));
+
+            assert_eq!(contract.transfer_name(name, default_accounts.bob), Ok(()));
+            assert_eq!(contract.resolve(name), Some(default_accounts.bob));
+            assert_eq!(contract.get_name(default_accounts.alice), None);
+            assert_eq!(contract.get_name(default_accounts.bob), Some(name));
+            assert_eq!(
+                contract.transfer_name(name, default_accounts.alice),
+                Err(Error::CallerIsNotOwner)
+            );
+        }
+
+        #[ink::test]
+        fn set_primary_works() {
+            let default_accounts = default_accounts();
+            let first = Hash::from([0x01; 32]);
+            let second = Hash::from([0x02; 32]);
+            let renamed = Hash::from([0x03; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            assert_eq!(contract.set_primary(first), Err(Error::UsernameNotFound));
+            contract.register(first).unwrap();
+            contract.register(second).unwrap();
+            assert_eq!(
+                contract.primary_name_of(default_accounts.alice),
+                Some(first)
+            );
+
+            assert_eq!(contract.set_primary(second), Ok(()));
+            assert_eq!(
+                contract.primary_name_of(default_accounts.alice),
+                Some(second)
+            );
+
+            contract.edit_username(second, renamed).unwrap();
+            assert_eq!(
+                contract.primary_name_of(default_accounts.alice),
+                Some(renamed)
+            );
+
+            set_next_caller(default_accounts.bob);
+            assert_eq!(contract.set_primary(first), Err(Error::CallerIsNotOwner));
+        }
+
+        #[ink::test]
+        fn primary_cleared_on_release() {
+            let default_accounts = default_accounts();
+            let first = Hash::from([0x01; 32]);
+            let second = Hash::from([0x02; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            contract.register(first).unwrap();
+            contract.register(second).unwrap();
+
+            contract.set_primary(first).unwrap();
+            contract.unregister(first).unwrap();
+            assert_eq!(
+                contract.primary_name_of(default_accounts.alice),
+                Some(second)
+            );
+
+            contract.set_primary(second).unwrap();
+            contract
+                .transfer_name(second, default_accounts.bob)
+                .unwrap();
+            assert_eq!(contract.primary_name_of(default_accounts.alice), None);
+            assert_eq!(contract.primary_name_of(default_accounts.bob), Some(second));
+        }
+
+        #[ink::test]
+        fn reverse_lookup_follows_remaining_names() {
+            let default_accounts = default_accounts();
+            let first = Hash::from([0x01; 32]);
+            let second = Hash::from([0x02; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            contract.register(first).unwrap();
+            contract.register(second).unwrap();
+
+            contract.unregister(second).unwrap();
+            assert_eq!(contract.get_name(default_accounts.alice), Some(first));
+            assert_eq!(contract.names_of(default_accounts.alice), vec![first]);
+
+            contract.unregister(first).unwrap();
+            assert_eq!(contract.get_name(default_accounts.alice), None);
+        }
+
+        #[ink::test]
+        fn reverse_lookup_matches_primary_name() {
+            let default_accounts = default_accounts();
+            let first = Hash::from([0x01; 32]);
+            let second = Hash::from([0x02; 32]);
+            let alice = default_accounts.alice;
+
+            set_next_caller(alice);
+            let mut contract = UkeHumanDns::new();
+            contract.register(first).unwrap();
+            contract.register(second).unwrap();
+            assert_eq!(contract.get_name(alice), Some(first));
+            assert_eq!(contract.get_name(alice), contract.primary_name_of(alice));
+
+            contract.set_primary(second).unwrap();
+            assert_eq!(contract.get_name(alice), Some(second));
+            assert_eq!(contract.get_name_batch(vec![alice]), vec![Some(second)]);
+
+            contract
+                .transfer_name(second, default_accounts.bob)
+                .unwrap();
+            assert_eq!(contract.get_name(alice), Some(first));
+            assert_eq!(contract.get_name(default_accounts.bob), Some(second));
+        }
+
+        #[ink::test]
+        fn primary_falls_back_to_earliest() {
+            let default_accounts = default_accounts();
+            let first = Hash::from([0x01; 32]);
+            let second = Hash::from([0x02; 32]);
+            let third = Hash::from([0x03; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            assert_eq!(contract.primary_name_of(default_accounts.alice), None);
+
+            contract.register_many(vec![first, second, third]).unwrap();
+            assert_eq!(
+                contract.primary_name_of(default_accounts.alice),
+                Some(first)
+            );
+
+            contract.unregister(first).unwrap();
+            assert_eq!(
+                contract.primary_name_of(default_accounts.alice),
+                Some(second)
+            );
+
+            contract.set_primary(third).unwrap();
+            assert_eq!(
+                contract.primary_name_of(default_accounts.alice),
+                Some(third)
+            );
+        }
+
+        #[ink::test]
+        fn set_address_works() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+            let new_name = Hash::from([0x02; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            assert_eq!(
+                contract.set_address(name, default_accounts.bob),
+                Err(Error::UsernameNotFound)
+            );
+            contract.register(name).unwrap();
+            assert_eq!(contract.set_address(name, default_accounts.bob), Ok(()));
+            assert_eq!(contract.resolve(name), Some(default_accounts.bob));
+            assert_eq!(contract.get_address(name), default_accounts.bob);
+            assert_eq!(contract.owner_of(name), Some(default_accounts.alice));
+
+            contract.edit_username(name, new_name).unwrap();
+            assert_eq!(contract.resolve(new_name), Some(default_accounts.bob));
+            assert_eq!(contract.owner_of(new_name), Some(default_accounts.alice));
+
+            set_next_caller(default_accounts.bob);
+            assert_eq!(
+                contract.set_address(new_name, default_accounts.bob),
+                Err(Error::CallerIsNotOwner)
+            );
+            assert_eq!(contract.unregister(new_name), Err(Error::CallerIsNotOwner));
+        }
+
+        #[ink::test]
+        fn offer_and_claim_works() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            contract.register(name).unwrap();
+            assert_eq!(contract.claim_name(name), Err(Error::NoPendingOffer));
+            assert_eq!(contract.offer_name(name, default_accounts.bob), Ok(()));
+
+            set_next_caller(default_accounts.charlie);
+            assert_eq!(contract.claim_name(name), Err(Error::NotOfferRecipient));
+
+            set_next_caller(default_accounts.bob);
+            assert_eq!(contract.claim_name(name), Ok(()));
+            assert_eq!(contract.resolve(name), Some(default_accounts.bob));
+            assert_eq!(contract.get_name(default_accounts.bob), Some(name));
+            assert_eq!(contract.claim_name(name), Err(Error::NoPendingOffer));
+        }
+
+        #[ink::test]
+        fn offer_cleared_on_release() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+            let new_name = Hash::from([0x02; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            contract.register(name).unwrap();
+            contract.offer_name(name, default_accounts.bob).unwrap();
+            contract.edit_username(name, new_name).unwrap();
+            contract.offer_name(new_name, default_accounts.bob).unwrap();
+            contract.unregister(new_name).unwrap();
+
+            set_next_caller(default_accounts.bob);
+            assert_eq!(contract.claim_name(name), Err(Error::NoPendingOffer));
+            assert_eq!(contract.claim_name(new_name), Err(Error::NoPendingOffer));
+        }
+
+        #[ink::test]
+        fn resolve_works() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            assert_eq!(contract.resolve(name), None);
+
+            assert_eq!(contract.register(name), Ok(0));
+            assert_eq!(contract.resolve(name), Some(default_accounts.alice));
+        }
+
+        #[ink::test]
+        fn exists_works() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            assert!(!contract.exists(name));
+            contract.register(name).unwrap();
+            assert!(contract.exists(name));
+            contract.unregister(name).unwrap();
+            assert!(!contract.exists(name));
+        }
+
+        #[ink::test]
+        fn registration_ids_work() {
+            let default_accounts = default_accounts();
+            let first = Hash::from([0x01; 32]);
+            let second = Hash::from([0x02; 32]);
+            let third = Hash::from([0x03; 32]);
+            let renamed = Hash::from([0x04; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            contract.register(first).unwrap();
+            contract.register(second).unwrap();
+            contract.unregister(first).unwrap();
+            contract.edit_username(second, renamed).unwrap();
+            ink_env::test::advance_block::<Environment>();
+            contract.register(third).unwrap();
+
+            assert_eq!(contract.registration_id_of(first), None);
+            assert_eq!(contract.registration_id_of(renamed), Some(1));
+            assert_eq!(contract.registration_id_of(third), Some(2));
+
+            let registrations: Vec<(u32, Timestamp)> = ink_env::test::recorded_events()
+                .filter_map(
+                    |event| match <Event as scale::Decode>::decode(&mut &event.data[..]) {
+                        Ok(Event::Register(Register { id, timestamp, .. })) => {
+                            Some((id, timestamp))
+                        }
+                        _ => None,
+                    },
+                )
+                .collect();
+            assert_eq!(registrations, vec![(0, 0), (1, 0), (2, 6)]);
+        }
+
+        #[ink::test]
+        fn owner_of_works() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            assert_eq!(contract.owner_of(name), None);
+            contract.register(name).unwrap();
+
+            set_next_caller(default_accounts.bob);
+            assert_eq!(contract.register(name), Err(Error::UsernameAlreadyExists));
+            assert_eq!(contract.owner_of(name), Some(default_accounts.alice));
+        }
+
+        #[ink::test]
+        fn expiry_works() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            assert_eq!(contract.set_ttl(10), Ok(()));
+            assert!(!contract.is_expired(name));
+            contract.register(name).unwrap();
+
+            ink_env::test::advance_block::<Environment>();
+            assert!(!contract.is_expired(name));
+            assert_eq!(contract.resolve(name), Some(default_accounts.alice));
+
+            ink_env::test::advance_block::<Environment>();
+            assert!(contract.is_expired(name));
+            assert_eq!(contract.resolve(name), None);
+            assert_eq!(contract.get_address(name), AccountId::default());
+
+            set_next_caller(default_accounts.bob);
+            assert_eq!(contract.set_ttl(0), Err(Error::NotAdmin));
+        }
+
+        #[ink::test]
+        fn time_remaining_works() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            contract.set_ttl(10).unwrap();
+            assert_eq!(contract.time_remaining(name), None);
+            contract.register(name).unwrap();
+            assert_eq!(contract.time_remaining(name), Some(10));
+
+            ink_env::test::advance_block::<Environment>();
+            assert_eq!(contract.time_remaining(name), Some(4));
+
+            ink_env::test::advance_block::<Environment>();
+            assert_eq!(contract.time_remaining(name), None);
+        }
+
+        #[ink::test]
+        fn grace_period_works() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+            let new_name = Hash::from([0x02; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            contract.set_ttl(5).unwrap();
+            assert_eq!(contract.set_grace_period(6), Ok(()));
+            assert_eq!(contract.name_status(name), NameStatus::Unregistered);
+            contract.register(name).unwrap();
+            assert_eq!(contract.name_status(name), NameStatus::Active);
+
+            ink_env::test::advance_block::<Environment>();
+            assert_eq!(contract.name_status(name), NameStatus::Grace);
+            assert!(contract.is_expired(name));
+            assert_eq!(contract.resolve(name), Some(default_accounts.alice));
+            assert_eq!(
+                contract.edit_username(name, new_name),
+                Err(Error::InGracePeriod)
+            );
+            assert_eq!(
+                contract.transfer_name(name, default_accounts.bob),
+                Err(Error::InGracePeriod)
+            );
+
+            set_next_caller(default_accounts.bob);
+            assert_eq!(contract.register(name), Err(Error::UsernameAlreadyExists));
+
+            ink_env::test::advance_block::<Environment>();
+            assert_eq!(contract.name_status(name), NameStatus::Expired);
+            assert_eq!(contract.resolve(name), None);
+            assert_eq!(contract.register(name), Ok(1));
+        }
+
+        /// Checks that the forward, reverse and ownership indexes agree with each other.
+        fn assert_consistent(contract: &UkeHumanDns, names: &[Hash], accounts: &[AccountId]) {
+            let registered: Vec<Hash> = names
+                .iter()
+                .copied()
+                .filter(|name| contract.owner_of(*name).is_some())
+                .collect();
+            assert_eq!(contract.total_registrations() as usize, registered.len());
+
+            let mut indexed = contract.names_paged(0, MAX_PAGE_SIZE);
+            indexed.sort();
+            let mut expected = registered.clone();
+            expected.sort();
+            assert_eq!(indexed, expected);
+
+            for name in &registered {
+                let owner = contract.owner_of(*name).unwrap();
+                assert!(contract.resolve(*name).is_some());
+                assert!(contract.names_of(owner).contains(name));
+            }
+            let mut held = 0;
+            for who in accounts {
+                let owned = contract.names_of(*who);
+                for name in &owned {
+                    assert_eq!(contract.owner_of(*name), Some(*who));
+                }
+                match contract.get_name(*who) {
+                    Some(name) => assert_eq!(contract.owner_of(name), Some(*who)),
+                    None => assert!(owned.is_empty()),
+                }
+                held += owned.len();
+            }
+            assert_eq!(held, registered.len());
+        }
+
+        #[ink::test]
+        fn random_operations_keep_indexes_consistent() {
+            let default_accounts = default_accounts();
+            let accounts = [
+                default_accounts.alice,
+                default_accounts.bob,
+                default_accounts.charlie,
+                default_accounts.django,
+            ];
+            let names: Vec<Hash> = (1..=6).map(|i| Hash::from([i; 32])).collect();
 
-            if self.get_address_or_default(old_name) != caller {
-                return Err(Error::CallerIsNotOwner);
+            for (i, seed) in [1u64, 42, 0xdead_beef].into_iter().enumerate() {
+                // A fresh callee per run, otherwise the contracts would share storage.
+                ink_env::test::set_callee::<Environment>(AccountId::from([0x10 + i as u8; 32]));
+                set_next_caller(default_accounts.alice);
+                let mut contract = UkeHumanDns::new();
+                let mut state = seed;
+                let mut next = |bound: usize| {
+                    state ^= state << 13;
+                    state ^= state >> 7;
+                    state ^= state << 17;
+                    (state % bound as u64) as usize
+                };
+
+                for _ in 0..200 {
+                    let caller = accounts[next(accounts.len())];
+                    let name = names[next(names.len())];
+                    let other_name = names[next(names.len())];
+                    let other = accounts[next(accounts.len())];
+                    set_next_caller(caller);
+                    let _ = match next(5) {
+                        0 => contract.register(name).map(|_| ()),
+                        1 => contract.edit_username(name, other_name),
+                        2 => contract.transfer_name(name, other),
+                        3 => contract.unregister(name),
+                        _ => contract.set_address(name, other),
+                    };
+                    assert_consistent(&contract, &names, &accounts);
+                }
             }
+        }
 
-            self.username_to_id.remove(&old_name);
-            self.username_to_id.insert(&new_name, &caller);
+        #[ink::test]
+        fn resolve_reads_two_entries() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+            let callee = ink_env::test::callee::<Environment>();
 
-            self.env().emit_event(EditUsername {
-                old_name,
-                new_name,
-                from: caller,
-            });
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            contract.register(name).unwrap();
 
-            Ok(())
+            let (reads, _) = ink_env::test::get_contract_storage_rw::<Environment>(&callee);
+            assert_eq!(contract.resolve(name), Some(default_accounts.alice));
+            let (reads_after, _) = ink_env::test::get_contract_storage_rw::<Environment>(&callee);
+            assert_eq!(reads_after - reads, 2);
         }
 
-        /// Returns the address given the hash or the default address.
-        fn get_address_or_default(&self, name: Hash) -> AccountId {
-            self.username_to_id
-                .get(&name)
-                .unwrap_or(self.default_address)
+        #[ink::test]
+        fn resolve_logged_works() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            contract.register(name).unwrap();
+
+            set_next_caller(default_accounts.bob);
+            assert_eq!(contract.resolve_logged(name), Some(default_accounts.alice));
+            assert_eq!(contract.resolve_logged(Hash::from([0x02; 32])), None);
+
+            let resolved: Vec<(Hash, AccountId)> = ink_env::test::recorded_events()
+                .filter_map(
+                    |event| match <Event as scale::Decode>::decode(&mut &event.data[..]) {
+                        Ok(Event::Resolved(Resolved { name, by })) => Some((name, by)),
+                        _ => None,
+                    },
+                )
+                .collect();
+            assert_eq!(
+                resolved,
+                vec![
+                    (name, default_accounts.bob),
+                    (Hash::from([0x02; 32]), default_accounts.bob)
+                ]
+            );
         }
-    }
 
-    #[cfg(test)]
-    mod tests {
+        #[ink::test]
+        fn alias_works() {
+            let default_accounts = default_accounts();
+            let names: Vec<Hash> = (1..=5).map(|i| Hash::from([i; 32])).collect();
 
-        use super::*;
-        use ink_lang as ink;
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            for name in &names {
+                contract.register(*name).unwrap();
+            }
+            contract
+                .set_address(names[4], default_accounts.eve)
+                .unwrap();
 
-        fn set_next_caller(caller: AccountId) {
-            ink_env::test::set_caller::<Environment>(caller);
+            set_next_caller(default_accounts.bob);
+            contract.register(Hash::from([0x09; 32])).unwrap();
+            assert_eq!(
+                contract.set_alias(Hash::from([0x09; 32]), names[0]),
+                Err(Error::CallerIsNotOwner)
+            );
+
+            set_next_caller(default_accounts.alice);
+            assert_eq!(
+                contract.set_alias(names[0], names[0]),
+                Err(Error::AliasLoop)
+            );
+            assert_eq!(contract.set_alias(names[0], names[1]), Ok(()));
+            assert_eq!(
+                contract.set_alias(names[1], names[0]),
+                Err(Error::AliasLoop)
+            );
+            assert_eq!(contract.set_alias(names[1], names[4]), Ok(()));
+            assert_eq!(contract.alias_of(names[0]), Some(names[1]));
+            assert_eq!(contract.resolve(names[0]), Some(default_accounts.eve));
+
+            contract.set_alias(names[2], names[0]).unwrap();
+            assert_eq!(contract.resolve(names[2]), Some(default_accounts.eve));
+            contract.set_alias(names[3], names[2]).unwrap();
+            assert_eq!(contract.resolve(names[3]), None);
+            assert_eq!(
+                contract.set_alias(names[4], names[3]),
+                Err(Error::AliasLoop)
+            );
+
+            assert_eq!(contract.remove_alias(names[1]), Ok(()));
+            assert_eq!(contract.resolve(names[0]), Some(default_accounts.alice));
+            contract
+                .transfer_name(names[0], default_accounts.bob)
+                .unwrap();
+            assert_eq!(contract.alias_of(names[0]), None);
+            assert_eq!(contract.resolve(names[0]), Some(default_accounts.bob));
         }
 
-        fn default_accounts() -> ink_env::test::DefaultAccounts<ink_env::DefaultEnvironment> {
-            ink_env::test::default_accounts::<Environment>()
+        #[ink::test]
+        fn alias_stops_resolving_when_target_changes_hands() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+            let target_name = Hash::from([0x02; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            contract.register(name).unwrap();
+            contract.register(target_name).unwrap();
+            contract.set_alias(name, target_name).unwrap();
+            contract
+                .transfer_name(target_name, default_accounts.eve)
+                .unwrap();
+
+            set_next_caller(default_accounts.eve);
+            contract
+                .set_address(target_name, default_accounts.eve)
+                .unwrap();
+            assert_eq!(contract.resolve(name), None);
+            assert_eq!(contract.owner_of(name), Some(default_accounts.alice));
+
+            contract
+                .transfer_name(target_name, default_accounts.alice)
+                .unwrap();
+            assert_eq!(contract.resolve(name), Some(default_accounts.alice));
         }
 
         #[ink::test]
-        fn default_works() {
-            let uke_human_dns = UkeHumanDns::new();
-            assert_eq!(uke_human_dns.default_address, Default::default());
+        fn alias_rejects_long_loops() {
+            let default_accounts = default_accounts();
+            let names: Vec<Hash> = (1..=4).map(|i| Hash::from([i; 32])).collect();
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            for name in &names {
+                contract.register(*name).unwrap();
+            }
+            for pair in names.windows(2) {
+                contract.set_alias(pair[0], pair[1]).unwrap();
+            }
+            assert_eq!(
+                contract.set_alias(names[3], names[0]),
+                Err(Error::AliasLoop)
+            );
+            assert_eq!(contract.alias_of(names[3]), None);
         }
 
         #[ink::test]
-        fn register_works() {
+        fn rename_drops_alias_closing_a_loop() {
+            let default_accounts = default_accounts();
+            let a = Hash::from([0x01; 32]);
+            let b = Hash::from([0x02; 32]);
+            let x = Hash::from([0x03; 32]);
+            let y = Hash::from([0x04; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            for name in [a, b, x, y] {
+                contract.register(name).unwrap();
+            }
+            contract.set_alias(a, b).unwrap();
+            contract.set_alias(x, a).unwrap();
+            contract.unregister(b).unwrap();
+
+            assert_eq!(contract.edit_username(x, b), Ok(()));
+            assert_eq!(contract.alias_of(b), None);
+            assert_eq!(contract.alias_of(a), Some(b));
+            assert_eq!(contract.resolve(a), Some(default_accounts.alice));
+            assert_eq!(contract.set_alias(y, a), Ok(()));
+            assert_eq!(contract.set_alias(b, y), Err(Error::AliasLoop));
+        }
+
+        #[ink::test]
+        fn lease_works() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+            let new_name = Hash::from([0x02; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            contract.register(name).unwrap();
+            assert_eq!(
+                contract.lease(name, default_accounts.bob, 0),
+                Err(Error::InvalidLease)
+            );
+            assert_eq!(contract.end_lease(name), Err(Error::NoLease));
+            assert_eq!(contract.lease(name, default_accounts.bob, 6), Ok(()));
+            assert_eq!(contract.resolve(name), Some(default_accounts.bob));
+            assert_eq!(contract.owner_of(name), Some(default_accounts.alice));
+
+            contract.edit_username(name, new_name).unwrap();
+            assert_eq!(contract.resolve(new_name), Some(default_accounts.bob));
+
+            ink_env::test::advance_block::<Environment>();
+            assert_eq!(contract.lease_of(new_name), None);
+            assert_eq!(contract.resolve(new_name), Some(default_accounts.alice));
+
+            contract
+                .lease(new_name, default_accounts.charlie, 100)
+                .unwrap();
+            set_next_caller(default_accounts.charlie);
+            assert_eq!(contract.end_lease(new_name), Err(Error::CallerIsNotOwner));
+
+            set_next_caller(default_accounts.alice);
+            assert_eq!(contract.end_lease(new_name), Ok(()));
+            assert_eq!(contract.resolve(new_name), Some(default_accounts.alice));
+        }
+
+        #[ink::test]
+        fn owner_at_works() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+            let other = Hash::from([0x02; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            ink_env::test::advance_block::<Environment>();
+            contract.register(name).unwrap();
+            ink_env::test::advance_block::<Environment>();
+            contract.transfer_name(name, default_accounts.bob).unwrap();
+            ink_env::test::advance_block::<Environment>();
+            set_next_caller(default_accounts.bob);
+            contract.unregister(name).unwrap();
+
+            assert_eq!(contract.owner_at(name, 0), None);
+            assert_eq!(contract.owner_at(name, 1), Some(default_accounts.alice));
+            assert_eq!(contract.owner_at(name, 2), Some(default_accounts.bob));
+            assert_eq!(contract.owner_at(name, 3), None);
+            assert_eq!(contract.owner_at(name, 100), None);
+
+            for _ in 0..4 {
+                ink_env::test::advance_block::<Environment>();
+                contract.register(other).unwrap();
+                contract.unregister(other).unwrap();
+            }
+            contract.register(other).unwrap();
+            assert_eq!(contract.owner_at(other, 4), None);
+            assert_eq!(contract.owner_at(other, 7), Some(default_accounts.bob));
+        }
+
+        #[ink::test]
+        fn is_active_owner_works() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            contract.set_ttl(5).unwrap();
+            contract.set_grace_period(6).unwrap();
+            assert!(!contract.is_active_owner(name, default_accounts.alice));
+
+            contract.register(name).unwrap();
+            assert!(contract.is_active_owner(name, default_accounts.alice));
+            assert!(!contract.is_active_owner(name, default_accounts.bob));
+
+            ink_env::test::advance_block::<Environment>();
+            assert!(contract.verify_owner(name, default_accounts.alice));
+            assert!(!contract.is_active_owner(name, default_accounts.alice));
+        }
+
+        #[ink::test]
+        fn renew_works() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            contract.set_ttl(10).unwrap();
+            assert_eq!(contract.renew(name), Err(Error::UsernameNotFound));
+            contract.register(name).unwrap();
+
+            ink_env::test::advance_block::<Environment>();
+            assert_eq!(contract.renew(name), Ok(()));
+            ink_env::test::advance_block::<Environment>();
+            assert!(!contract.is_expired(name));
+
+            set_next_caller(default_accounts.bob);
+            assert_eq!(contract.renew(name), Err(Error::CallerIsNotOwner));
+
+            set_next_caller(default_accounts.alice);
+            ink_env::test::advance_block::<Environment>();
+            ink_env::test::advance_block::<Environment>();
+            assert_eq!(contract.renew(name), Err(Error::NameExpired));
+        }
+
+        #[ink::test]
+        fn sponsored_renew_works() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new_configured(100, 10, u32::MAX);
+            ink_env::test::set_value_transferred::<Environment>(100);
+            contract.register(name).unwrap();
+            ink_env::test::advance_block::<Environment>();
+
+            set_next_caller(default_accounts.bob);
+            ink_env::test::set_value_transferred::<Environment>(99);
+            assert_eq!(contract.renew(name), Err(Error::InsufficientFee));
+            ink_env::test::set_value_transferred::<Environment>(100);
+            assert_eq!(contract.renew(name), Ok(()));
+            assert_eq!(contract.owner_of(name), Some(default_accounts.alice));
+            assert_eq!(contract.time_remaining(name), Some(10));
+            assert_eq!(contract.total_fees(), 200);
+        }
+
+        #[ink::test]
+        fn register_reclaims_expired() {
             let default_accounts = default_accounts();
             let name = Hash::from([0x01; 32]);
 
             set_next_caller(default_accounts.alice);
             let mut contract = UkeHumanDns::new();
+            contract.set_ttl(10).unwrap();
+            contract.register(name).unwrap();
+            contract.set_primary(name).unwrap();
 
-            assert_eq!(contract.register(name), Ok(()));
+            set_next_caller(default_accounts.bob);
             assert_eq!(contract.register(name), Err(Error::UsernameAlreadyExists));
+
+            ink_env::test::advance_block::<Environment>();
+            ink_env::test::advance_block::<Environment>();
+            assert_eq!(contract.register(name), Ok(1));
+            assert_eq!(contract.resolve(name), Some(default_accounts.bob));
+            assert_eq!(contract.get_name(default_accounts.alice), None);
+            assert_eq!(contract.primary_name_of(default_accounts.alice), None);
+            assert_eq!(contract.total_registrations(), 1);
+            assert_eq!(ink_env::test::recorded_events().count(), 3);
         }
 
         #[ink::test]
-        fn edit_works() {
+        fn commit_reveal_works() {
             let default_accounts = default_accounts();
-            let old_name = Hash::from([0x01; 32]);
-            let new_name = Hash::from([0x02; 32]);
+            let name = Hash::from([0x01; 32]);
+            let secret = [0x07; 32];
 
             set_next_caller(default_accounts.alice);
             let mut contract = UkeHumanDns::new();
-            contract.register(old_name).unwrap();
+            contract.set_min_commitment_age(6).unwrap();
+            assert_eq!(contract.reveal(name, secret), Err(Error::NoCommitment));
 
-            assert_eq!(contract.edit_username(old_name, new_name), Ok(()));
-            assert_eq!(contract.get_address(new_name), default_accounts.alice);
+            let commitment = contract.make_commitment(name, secret, default_accounts.alice);
+            assert_eq!(contract.commit(commitment), Ok(()));
+            assert_eq!(contract.reveal(name, secret), Err(Error::CommitmentTooNew));
+
+            set_next_caller(default_accounts.bob);
+            ink_env::test::advance_block::<Environment>();
+            assert_eq!(contract.reveal(name, secret), Err(Error::NoCommitment));
+
+            set_next_caller(default_accounts.alice);
+            assert_eq!(contract.reveal(name, secret), Ok(()));
+            assert_eq!(contract.resolve(name), Some(default_accounts.alice));
+            assert_eq!(contract.reveal(name, secret), Err(Error::NoCommitment));
         }
 
         #[ink::test]
-        fn get_address_works() {
+        fn commit_reveal_cannot_be_front_run() {
             let default_accounts = default_accounts();
             let name = Hash::from([0x01; 32]);
+            let secret = [0x07; 32];
 
             set_next_caller(default_accounts.alice);
             let mut contract = UkeHumanDns::new();
+            contract.set_min_commitment_age(6).unwrap();
+            let commitment = contract.make_commitment(name, secret, default_accounts.alice);
+            contract.commit(commitment).unwrap();
 
-            assert_eq!(contract.register(name), Ok(()));
-            assert_eq!(contract.get_address(name), default_accounts.alice);
+            set_next_caller(default_accounts.bob);
+            assert_eq!(contract.commit(commitment), Ok(()));
+            assert_eq!(contract.register(name), Err(Error::CommitmentRequired));
+            assert_eq!(
+                contract.register_many(vec![name]),
+                Err(Error::CommitmentRequired)
+            );
+            ink_env::test::advance_block::<Environment>();
+            assert_eq!(contract.reveal(name, secret), Err(Error::NoCommitment));
+
+            set_next_caller(default_accounts.alice);
+            assert_eq!(contract.reveal(name, secret), Ok(()));
+            assert_eq!(contract.owner_of(name), Some(default_accounts.alice));
+        }
+
+        #[ink::test]
+        fn pubkey_works() {
+            let default_accounts = default_accounts();
+            let key = [0x0a; 32];
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            assert_eq!(contract.get_pubkey(default_accounts.alice), None);
+
+            assert_eq!(contract.set_pubkey(key), Ok(()));
+            assert_eq!(contract.get_pubkey(default_accounts.alice), Some(key));
+            assert_eq!(contract.get_pubkey(default_accounts.bob), None);
+        }
+
+        #[ink::test]
+        fn pubkey_rotation_emits_old_key() {
+            let default_accounts = default_accounts();
+            let first = [0x0a; 32];
+            let second = [0x0b; 32];
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            contract.set_pubkey(first).unwrap();
+            contract.set_pubkey(second).unwrap();
+
+            let rotations: Vec<(Option<[u8; 32]>, [u8; 32])> = ink_env::test::recorded_events()
+                .filter_map(
+                    |event| match <Event as scale::Decode>::decode(&mut &event.data[..]) {
+                        Ok(Event::PubkeyRotated(PubkeyRotated {
+                            old_key, new_key, ..
+                        })) => Some((old_key, new_key)),
+                        _ => None,
+                    },
+                )
+                .collect();
+            assert_eq!(rotations, vec![(None, first), (Some(first), second)]);
+        }
+
+        #[ink::test]
+        fn address_records_work() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+            let btc = 0;
+            let addr = b"bc1qexample".to_vec();
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            contract.register(name).unwrap();
+            assert_eq!(contract.get_addr(name, btc), None);
+            assert_eq!(contract.set_addr(name, btc, addr.clone()), Ok(()));
+            assert_eq!(contract.get_addr(name, btc), Some(addr));
+            assert_eq!(
+                contract.get_addr(name, NATIVE_COIN_TYPE),
+                Some([0x01; 32].to_vec())
+            );
+            assert_eq!(
+                contract.set_addr(name, NATIVE_COIN_TYPE, [0x02; 32].to_vec()),
+                Err(Error::InvalidCoinType)
+            );
+            assert_eq!(
+                contract.set_addr(name, btc, [0x00; 129].to_vec()),
+                Err(Error::ValueTooLong)
+            );
+
+            set_next_caller(default_accounts.bob);
+            assert_eq!(
+                contract.set_addr(name, btc, b"bc1q".to_vec()),
+                Err(Error::NotAuthorized)
+            );
+        }
+
+        #[ink::test]
+        fn address_records_follow_registration() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+            let renamed = Hash::from([0x02; 32]);
+            let btc = 0;
+            let addr = b"bc1qexample".to_vec();
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            contract.register(name).unwrap();
+            contract.set_addr(name, btc, addr.clone()).unwrap();
+            contract.edit_username(name, renamed).unwrap();
+            assert_eq!(contract.get_addr(name, btc), None);
+            assert_eq!(contract.get_addr(renamed, btc), Some(addr.clone()));
+
+            contract.unregister(renamed).unwrap();
+            assert_eq!(contract.get_addr(renamed, btc), None);
+            set_next_caller(default_accounts.bob);
+            contract.register(renamed).unwrap();
+            assert_eq!(contract.get_addr(renamed, btc), None);
+
+            set_next_caller(default_accounts.alice);
+            contract.register(name).unwrap();
+            for coin_type in 0..MAX_ADDR_RECORDS {
+                contract.set_addr(name, coin_type, addr.clone()).unwrap();
+            }
+            assert_eq!(
+                contract.set_addr(name, MAX_ADDR_RECORDS, addr.clone()),
+                Err(Error::TooManyRecords)
+            );
+            assert_eq!(contract.set_addr(name, btc, b"bc1q".to_vec()), Ok(()));
+        }
+
+        #[ink::test]
+        fn reclaim_drops_address_records() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+            let btc = 0;
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            contract.set_ttl(10).unwrap();
+            contract.register(name).unwrap();
+            contract.set_addr(name, btc, b"bc1qalice".to_vec()).unwrap();
+
+            ink_env::test::advance_block::<Environment>();
+            ink_env::test::advance_block::<Environment>();
+            assert_eq!(contract.get_addr(name, btc), None);
+
+            set_next_caller(default_accounts.bob);
+            contract.register(name).unwrap();
+            assert_eq!(contract.get_addr(name, btc), None);
+        }
+
+        #[ink::test]
+        fn text_records_work() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+            let key = b"website".to_vec();
+            let value = b"https://uke.chat".to_vec();
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            assert_eq!(
+                contract.set_text(name, key.clone(), value.clone()),
+                Err(Error::UsernameNotFound)
+            );
+            contract.register(name).unwrap();
+            assert_eq!(contract.get_text(name, key.clone()), None);
+
+            assert_eq!(contract.set_text(name, key.clone(), value.clone()), Ok(()));
+            assert_eq!(contract.get_text(name, key.clone()), Some(value.clone()));
+
+            contract.set_max_text_len(4).unwrap();
+            assert_eq!(
+                contract.set_text(name, b"bio".to_vec(), value.clone()),
+                Err(Error::ValueTooLong)
+            );
+            assert_eq!(
+                contract.set_text(name, key.clone(), b"hi".to_vec()),
+                Err(Error::ValueTooLong)
+            );
+
+            set_next_caller(default_accounts.bob);
+            assert_eq!(
+                contract.set_text(name, b"bio".to_vec(), b"hi".to_vec()),
+                Err(Error::NotAuthorized)
+            );
+        }
+
+        #[ink::test]
+        fn delete_text_works() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            assert_eq!(
+                contract.delete_text(name, b"bio".to_vec()),
+                Err(Error::UsernameNotFound)
+            );
+            contract.register(name).unwrap();
+            contract
+                .set_text(name, b"bio".to_vec(), b"hi".to_vec())
+                .unwrap();
+            let version = contract.meta_version_of(name);
+
+            assert_eq!(contract.delete_text(name, b"url".to_vec()), Ok(()));
+            assert_eq!(contract.meta_version_of(name), version);
+
+            set_next_caller(default_accounts.bob);
+            assert_eq!(
+                contract.delete_text(name, b"bio".to_vec()),
+                Err(Error::NotAuthorized)
+            );
+
+            set_next_caller(default_accounts.alice);
+            assert_eq!(contract.delete_text(name, b"bio".to_vec()), Ok(()));
+            assert_eq!(contract.get_text(name, b"bio".to_vec()), None);
+            assert_eq!(contract.profile_keys(name), Vec::<Vec<u8>>::new());
+            assert_eq!(contract.meta_version_of(name), version + 1);
+            assert_eq!(ink_env::test::recorded_events().count(), 2);
+        }
+
+        #[ink::test]
+        fn profile_keys_work() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+            let renamed = Hash::from([0x02; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            contract.register(name).unwrap();
+            assert_eq!(contract.profile_keys(name), Vec::<Vec<u8>>::new());
+
+            contract
+                .set_text(name, b"bio".to_vec(), b"hi".to_vec())
+                .unwrap();
+            contract
+                .set_text(name, b"url".to_vec(), b"uke.chat".to_vec())
+                .unwrap();
+            contract
+                .set_text(name, b"bio".to_vec(), b"hello".to_vec())
+                .unwrap();
+            assert_eq!(
+                contract.profile_keys(name),
+                vec![b"bio".to_vec(), b"url".to_vec()]
+            );
+
+            contract
+                .set_text(name, b"bio".to_vec(), Vec::new())
+                .unwrap();
+            assert_eq!(contract.profile_keys(name), vec![b"url".to_vec()]);
+            assert_eq!(contract.get_text(name, b"bio".to_vec()), None);
+
+            contract.edit_username(name, renamed).unwrap();
+            assert_eq!(contract.profile_keys(name), Vec::<Vec<u8>>::new());
+            assert_eq!(contract.profile_keys(renamed), vec![b"url".to_vec()]);
+            assert_eq!(
+                contract.get_text(renamed, b"url".to_vec()),
+                Some(b"uke.chat".to_vec())
+            );
+
+            for i in 1..MAX_TEXT_RECORDS as u8 {
+                contract.set_text(renamed, vec![i], b"x".to_vec()).unwrap();
+            }
+            assert_eq!(
+                contract.set_text(renamed, b"bio".to_vec(), b"x".to_vec()),
+                Err(Error::TooManyRecords)
+            );
+
+            contract.unregister(renamed).unwrap();
+            assert_eq!(contract.profile_keys(renamed), Vec::<Vec<u8>>::new());
+            assert_eq!(contract.get_text(renamed, b"url".to_vec()), None);
+        }
+
+        #[ink::test]
+        fn operator_works() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+            let key = b"bio".to_vec();
+            let value = b"hi".to_vec();
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            contract.register(name).unwrap();
+            assert_eq!(contract.set_operator(name, default_accounts.bob), Ok(()));
+            assert_eq!(contract.operator_of(name), Some(default_accounts.bob));
+
+            set_next_caller(default_accounts.bob);
+            assert_eq!(contract.set_text(name, key.clone(), value.clone()), Ok(()));
+            assert_eq!(contract.get_text(name, key.clone()), Some(value.clone()));
+            assert_eq!(
+                contract.transfer_name(name, default_accounts.bob),
+                Err(Error::CallerIsNotOwner)
+            );
+            assert_eq!(contract.unregister(name), Err(Error::CallerIsNotOwner));
+
+            set_next_caller(default_accounts.alice);
+            contract
+                .transfer_name(name, default_accounts.charlie)
+                .unwrap();
+            assert_eq!(contract.operator_of(name), None);
+
+            set_next_caller(default_accounts.bob);
+            assert_eq!(
+                contract.set_text(name, key, value),
+                Err(Error::NotAuthorized)
+            );
+        }
+
+        #[ink::test]
+        fn resolve_full_works() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+            let key = [0x0a; 32];
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            assert_eq!(contract.resolve_full(name), None);
+            contract.register(name).unwrap();
+            assert_eq!(
+                contract.resolve_full(name),
+                Some((default_accounts.alice, None))
+            );
+
+            contract.set_pubkey(key).unwrap();
+            assert_eq!(
+                contract.resolve_full(name),
+                Some((default_accounts.alice, Some(key)))
+            );
+        }
+
+        #[ink::test]
+        fn get_name_works() {
+            let default_accounts = default_accounts();
+            let name = Hash::from([0x01; 32]);
+
+            set_next_caller(default_accounts.alice);
+            let mut contract = UkeHumanDns::new();
+            assert_eq!(contract.get_name(default_accounts.alice), None);
+
+            assert_eq!(contract.register(name), Ok(0));
+            assert_eq!(contract.get_name(default_accounts.alice), Some(name));
+            assert_eq!(contract.get_name(default_accounts.bob), None);
         }
     }
 }